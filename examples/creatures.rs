@@ -150,6 +150,7 @@ async fn main() {
                         format!("OnHit - Status: {} {duration}", effect.name)
                     }
                     Effect::Heal { amount } => format!("OnHit - Heal: {amount}"),
+                    _ => format!("OnHit - {onhit:?}"),
                 }
             } else {
                 "".to_string()
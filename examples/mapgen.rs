@@ -0,0 +1,191 @@
+use archivist::mission::{
+    BUILDER_KINDS, BuilderMap, Map, TileKind, builder_chain, random_builder_kind,
+};
+use archivist::prelude::*;
+use macroquad::shapes::{draw_circle, draw_rectangle};
+use macroquad::text::draw_text;
+use macroquad::window::{Conf, clear_background};
+
+const TILE_SIZE: f32 = 11.0;
+
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Map Generation Viewer".to_string(),
+        window_width: 900,
+        window_height: 700,
+        ..Default::default()
+    }
+}
+
+// Same steps as generate_random_map, with the builder picked by the seed unless one is forced
+fn generate(seed: u64, forced_kind: Option<usize>, difficulty: u32) -> (usize, BuilderMap) {
+    let mut rng = GameRng::new(seed).generator();
+    let rolled_kind = random_builder_kind(&mut rng);
+    let kind = forced_kind.unwrap_or(rolled_kind);
+    let build = builder_chain(kind, &mut rng, difficulty)
+        .with_snapshots()
+        .build(&mut rng);
+    (kind, build)
+}
+
+fn draw_map(map: &Map) {
+    for y in 0..SCREEN_HEIGHT {
+        for x in 0..SCREEN_WIDTH {
+            let color = match map.get(Point::new(x, y)).kind {
+                TileKind::Wall => DARKGRAY,
+                TileKind::Floor => LIGHTGRAY,
+                TileKind::Door { .. } => BROWN,
+                TileKind::LockedDoor => ORANGE,
+                TileKind::Exit => GREEN,
+                TileKind::StairsDown => PURPLE,
+            };
+            draw_rectangle(
+                x as f32 * TILE_SIZE,
+                y as f32 * TILE_SIZE,
+                TILE_SIZE,
+                TILE_SIZE,
+                color,
+            );
+        }
+    }
+}
+
+fn draw_marker(position: Point, color: Color) {
+    draw_circle(
+        (position.x as f32 + 0.5) * TILE_SIZE,
+        (position.y as f32 + 0.5) * TILE_SIZE,
+        TILE_SIZE / 2.5,
+        color,
+    );
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut seed = 1;
+    let mut forced_kind: Option<usize> = None;
+    let mut difficulty = 1;
+    let (mut kind, mut build) = generate(seed, forced_kind, difficulty);
+    let mut frame: usize = 0;
+    let mut playing = true;
+
+    loop {
+        clear_background(BLACK);
+
+        let mut regenerate = false;
+        if is_key_pressed(KeyCode::Space) {
+            playing = !playing;
+        } else if is_key_pressed(KeyCode::Left) {
+            playing = false;
+            frame = frame.saturating_sub(1);
+        } else if is_key_pressed(KeyCode::Right) {
+            playing = false;
+            frame += 1;
+        } else if is_key_pressed(KeyCode::Home) {
+            frame = 0;
+        } else if is_key_pressed(KeyCode::End) {
+            frame = build.snapshots.len();
+        } else if is_key_pressed(KeyCode::R) {
+            regenerate = true;
+        } else if is_key_pressed(KeyCode::N) {
+            seed = GameRng::from_time().rand() as u64;
+            regenerate = true;
+        } else if is_key_pressed(KeyCode::Up) {
+            forced_kind = match forced_kind {
+                None => Some(0),
+                Some(k) if k + 1 < BUILDER_KINDS.len() => Some(k + 1),
+                Some(_) => None,
+            };
+            regenerate = true;
+        } else if is_key_pressed(KeyCode::Down) {
+            forced_kind = match forced_kind {
+                None => Some(BUILDER_KINDS.len() - 1),
+                Some(0) => None,
+                Some(k) => Some(k - 1),
+            };
+            regenerate = true;
+        } else if is_key_pressed(KeyCode::Key1) {
+            difficulty = 1;
+            regenerate = true;
+        } else if is_key_pressed(KeyCode::Key2) {
+            difficulty = 2;
+            regenerate = true;
+        } else if is_key_pressed(KeyCode::Key3) {
+            difficulty = 3;
+            regenerate = true;
+        }
+
+        if regenerate {
+            (kind, build) = generate(seed, forced_kind, difficulty);
+            frame = 0;
+            playing = true;
+        }
+        if playing {
+            frame += 1;
+        }
+
+        // One past the last snapshot shows the finished level with everything placed on it
+        frame = frame.min(build.snapshots.len());
+        let finished = frame == build.snapshots.len();
+        if finished {
+            draw_map(&build.map);
+            for character in &build.characters {
+                draw_marker(character.position, RED);
+            }
+            for (position, item) in &build.items {
+                let color = match item.name.as_str() {
+                    "Runestone" => GOLD,
+                    "Vault Key" => ORANGE,
+                    _ => SKYBLUE,
+                };
+                draw_marker(*position, color);
+            }
+        } else {
+            draw_map(&build.snapshots[frame]);
+        }
+
+        let text_y = SCREEN_HEIGHT as f32 * TILE_SIZE + 25.0;
+        let builder = match forced_kind {
+            Some(_) => BUILDER_KINDS[kind].to_string(),
+            None => format!("{} (from seed)", BUILDER_KINDS[kind]),
+        };
+        draw_text(
+            &format!("Seed: {seed}   Builder: {builder}   Difficulty: {difficulty}"),
+            10.0,
+            text_y,
+            20.0,
+            WHITE,
+        );
+        let step = if finished {
+            "Finished".to_string()
+        } else {
+            format!("Snapshot {} / {}", frame + 1, build.snapshots.len())
+        };
+        draw_text(
+            &format!(
+                "{step}   Monsters: {}   Items: {}",
+                build.characters.len(),
+                build.items.len()
+            ),
+            10.0,
+            text_y + 22.0,
+            20.0,
+            WHITE,
+        );
+        draw_text(
+            "Space: play/pause  Left/Right: step  Home/End: first/last  R: same seed  N: new seed",
+            10.0,
+            text_y + 44.0,
+            18.0,
+            GRAY,
+        );
+        draw_text(
+            "Up/Down: builder  1-3: difficulty  Red: monsters  Gold: Runestone  Blue: loot",
+            10.0,
+            text_y + 64.0,
+            18.0,
+            GRAY,
+        );
+
+        macroquad::window::next_frame().await
+    }
+}
@@ -0,0 +1,346 @@
+// Headless balance simulator
+//
+// Runs every starting kit against every monster at every difficulty and
+// writes the win rates and remaining health to balance.csv and balance.json
+//
+// With --campaigns the autopilot instead plays full campaigns with each
+// kit and writes how far it got to campaigns.csv and campaigns.json
+//
+// archivist-sim [--fights 1000] [--campaigns 0] [--seed 1] [--output .]
+
+use std::path::PathBuf;
+
+use archivist::Difficulty;
+use archivist::campaign::{
+    CampaignState, EquipmentOption, RunSetup, RuneKinds, UpgradeState, load_equipment_options,
+};
+use archivist::mission::*;
+use archivist::prelude::*;
+
+const ARENA_SIZE: i32 = 12;
+const MONSTER_STARTING_DISTANCE: i32 = 6;
+const MAX_TURNS_PER_FIGHT: usize = 1000;
+const MAX_TURNS_PER_MISSION: usize = 20000;
+const HEALTH_BUCKETS: usize = 10;
+
+struct SimOptions {
+    fights: usize,
+    campaigns: usize,
+    seed: u64,
+    output: PathBuf,
+}
+
+impl SimOptions {
+    fn parse() -> Self {
+        let mut options = SimOptions {
+            fights: 1000,
+            campaigns: 0,
+            seed: 1,
+            output: PathBuf::from("."),
+        };
+
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let value = args.next();
+            match (arg.as_str(), value) {
+                ("--fights", Some(value)) => {
+                    options.fights = value.parse().expect("--fights should be a number")
+                }
+                ("--campaigns", Some(value)) => {
+                    options.campaigns = value.parse().expect("--campaigns should be a number")
+                }
+                ("--seed", Some(value)) => {
+                    options.seed = value.parse().expect("--seed should be a number")
+                }
+                ("--output", Some(value)) => options.output = PathBuf::from(value),
+                _ => {
+                    eprintln!(
+                        "Usage: archivist-sim [--fights 1000] [--campaigns 0] [--seed 1] [--output .]"
+                    );
+                    std::process::exit(1);
+                }
+            }
+        }
+        options
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FightSummary {
+    kit: String,
+    difficulty: Difficulty,
+    monster: String,
+    monster_difficulty: Option<u32>,
+    fights: usize,
+    wins: usize,
+    win_rate: f32,
+    average_remaining_health: f32,
+    median_remaining_health: i32,
+    // Remaining health percentage of wins, in 10% buckets
+    remaining_health_distribution: Vec<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct CampaignSummary {
+    kit: String,
+    difficulty: Difficulty,
+    campaigns: usize,
+    victories: usize,
+    average_missions_completed: f32,
+    // How many campaigns ended after completing 0, 1, 2... missions
+    missions_completed_distribution: Vec<usize>,
+}
+
+enum FightResult {
+    Won { remaining_health: i32 },
+    Lost,
+    Timeout,
+}
+
+fn main() {
+    let options = SimOptions::parse();
+    let data = Data::load().expect("Able to load data");
+
+    if options.campaigns > 0 {
+        run_campaigns(&options);
+        return;
+    }
+
+    let mut summaries = vec![];
+    for kit in load_equipment_options() {
+        for difficulty in [Difficulty::Normal, Difficulty::Easy] {
+            for monster in data.get_all_enemies() {
+                summaries.push(run_matchup(&options, &data, &kit, difficulty, &monster));
+            }
+        }
+    }
+
+    write_csv(&options, &summaries);
+    write_json(&options, &summaries);
+}
+
+fn run_matchup(
+    options: &SimOptions,
+    data: &Data,
+    kit: &EquipmentOption,
+    difficulty: Difficulty,
+    monster: &str,
+) -> FightSummary {
+    let mut remaining = vec![];
+    let mut max_health = 1;
+
+    for i in 0..options.fights {
+        let player = kit.outfit_character(difficulty);
+        max_health = player.health.max;
+
+        let seed = options.seed.wrapping_add(i as u64);
+        match run_fight(player, data.get_character(monster), seed) {
+            FightResult::Won { remaining_health } => remaining.push(remaining_health),
+            FightResult::Lost | FightResult::Timeout => {}
+        }
+    }
+
+    remaining.sort();
+    let wins = remaining.len();
+
+    let mut distribution = vec![0; HEALTH_BUCKETS];
+    for health in &remaining {
+        let bucket = (*health as usize * HEALTH_BUCKETS) / max_health as usize;
+        distribution[bucket.min(HEALTH_BUCKETS - 1)] += 1;
+    }
+
+    FightSummary {
+        kit: kit.name.clone(),
+        difficulty,
+        monster: monster.to_string(),
+        monster_difficulty: data.get_character_info(monster).difficulty,
+        fights: options.fights,
+        wins,
+        win_rate: wins as f32 / options.fights as f32,
+        average_remaining_health: if wins > 0 {
+            remaining.iter().sum::<i32>() as f32 / wins as f32
+        } else {
+            0.0
+        },
+        median_remaining_health: remaining.get(wins / 2).copied().unwrap_or(0),
+        remaining_health_distribution: distribution,
+    }
+}
+
+fn create_arena(mut player: Character, mut monster: Character, seed: u64) -> MissionState {
+    let mut map = Map::new_filled(MapTheme::Stone);
+    Rect::with_size(1, 1, ARENA_SIZE, ARENA_SIZE).for_each(|p| {
+        map.set(
+            p,
+            MapTile {
+                kind: TileKind::Floor,
+                known: true,
+                variation: 0,
+            },
+        );
+    });
+
+    let center = ARENA_SIZE / 2;
+    player.position = Point::new(center - MONSTER_STARTING_DISTANCE / 2, center);
+    monster.position = Point::new(center + MONSTER_STARTING_DISTANCE / 2, center);
+
+    let campaign = CampaignState::new(player.clone());
+    let mut level = LevelState::new(map, player.id, vec![player, monster], vec![]);
+    level.rng = GameRng::new(seed);
+
+    MissionState {
+        level,
+        frame: 0,
+        current_actor: CurrentActor::PlayerStandardAction,
+        mission_complete: false,
+        campaign,
+        active_rune: RuneKinds::Fire,
+        replay: None,
+        controller: PlayerController::Autopilot,
+        floors: vec![None],
+        depth: 0,
+    }
+}
+
+fn run_fight(player: Character, monster: Character, seed: u64) -> FightResult {
+    let monster_id = monster.id;
+    let mut state = create_arena(player, monster, seed);
+    let mut screen = EmptyScreen {};
+
+    for _ in 0..MAX_TURNS_PER_FIGHT {
+        if let Some(GameFlow::Dead(_)) = state.process_turns(&mut screen) {
+            return FightResult::Lost;
+        }
+        if state.level.find_character(monster_id).is_none() {
+            return FightResult::Won {
+                remaining_health: state.level.get_player().map_or(0, |p| p.health.current),
+            };
+        }
+    }
+    FightResult::Timeout
+}
+
+fn run_campaigns(options: &SimOptions) {
+    let mut summaries = vec![];
+    for kit in load_equipment_options() {
+        for difficulty in [Difficulty::Normal, Difficulty::Easy] {
+            let mut distribution = vec![0; RuneKinds::all().len() + 1];
+            for i in 0..options.campaigns {
+                let seed = options.seed.wrapping_add(i as u64);
+                distribution[run_campaign(&kit, difficulty, seed) as usize] += 1;
+            }
+
+            let completed: usize = distribution.iter().enumerate().map(|(i, c)| i * c).sum();
+            summaries.push(CampaignSummary {
+                kit: kit.name.clone(),
+                difficulty,
+                campaigns: options.campaigns,
+                victories: *distribution.last().unwrap(),
+                average_missions_completed: completed as f32 / options.campaigns as f32,
+                missions_completed_distribution: distribution,
+            });
+        }
+    }
+
+    let mut csv = String::from("kit,difficulty,campaigns,victories,average_missions_completed\n");
+    for summary in &summaries {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{:.2}\n",
+            summary.kit,
+            summary.difficulty,
+            summary.campaigns,
+            summary.victories,
+            summary.average_missions_completed
+        ));
+    }
+    write_output(options, "campaigns.csv", csv);
+    write_output(
+        options,
+        "campaigns.json",
+        serde_json::to_string_pretty(&summaries).expect("Unable to serialize results"),
+    );
+}
+
+// Returns how many missions were completed before dying (or running out of time)
+fn run_campaign(kit: &EquipmentOption, difficulty: Difficulty, seed: u64) -> u32 {
+    let rng = GameRng::new(seed);
+    let mut campaign =
+        CampaignState::with_setup(kit.outfit_character(difficulty), &RunSetup::custom(seed));
+
+    loop {
+        let Some(rune) = campaign.next_rune() else {
+            return campaign.completed_mission_count();
+        };
+
+        let mut mission =
+            MissionState::new_with_seed(campaign.clone(), rune, campaign.mission_seed());
+        mission.replay = None;
+        mission.controller = PlayerController::Autopilot;
+        if !run_mission(&mut mission) {
+            return campaign.completed_mission_count();
+        }
+
+        campaign.collected_runes.insert(rune);
+        if campaign.game_complete() {
+            return campaign.completed_mission_count();
+        }
+        // Goes on without an upgrade when the starting grid crafts nothing
+        let mut upgrade = UpgradeState::new(campaign, rune);
+        campaign = upgrade
+            .select(rng.gen_range(0, upgrade.craftable_count() as i32) as usize)
+            .unwrap_or_else(|| upgrade.campaign().clone());
+    }
+}
+
+fn run_mission(state: &mut MissionState) -> bool {
+    let mut screen = EmptyScreen {};
+
+    for _ in 0..MAX_TURNS_PER_MISSION {
+        match state.process_turns(&mut screen) {
+            Some(GameFlow::Dead(_)) => return false,
+            Some(_) => return state.mission_complete,
+            None => {}
+        }
+    }
+    false
+}
+
+fn write_output(options: &SimOptions, filename: &str, contents: String) {
+    let path = options.output.join(filename);
+    std::fs::write(&path, contents).unwrap_or_else(|e| panic!("Unable to write {filename}: {e}"));
+    println!("Wrote {}", path.display());
+}
+
+fn write_csv(options: &SimOptions, summaries: &[FightSummary]) {
+    let mut csv = String::from(
+        "kit,difficulty,monster,monster_difficulty,fights,wins,win_rate,average_remaining_health,median_remaining_health\n",
+    );
+    for summary in summaries {
+        csv.push_str(&format!(
+            "{},{:?},{},{},{},{},{:.3},{:.1},{}\n",
+            summary.kit,
+            summary.difficulty,
+            summary.monster,
+            summary
+                .monster_difficulty
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            summary.fights,
+            summary.wins,
+            summary.win_rate,
+            summary.average_remaining_health,
+            summary.median_remaining_health
+        ));
+    }
+
+    write_output(options, "balance.csv", csv);
+}
+
+fn write_json(options: &SimOptions, summaries: &[FightSummary]) {
+    write_output(
+        options,
+        "balance.json",
+        serde_json::to_string_pretty(summaries).expect("Unable to serialize results"),
+    );
+}
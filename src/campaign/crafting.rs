@@ -0,0 +1,126 @@
+use crate::{campaign::RuneKinds, prelude::*};
+
+pub const CRAFTING_GRID_SIZE: i32 = 3;
+
+// What has to be on the crafting grid for an upgrade to come out of it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recipe {
+    // Exactly these runes, no more, so an empty recipe is crafted from an empty grid
+    #[serde(default)]
+    pub runes: Vec<RuneKinds>,
+    // Pairs that have to sit side by side (not diagonally)
+    #[serde(default)]
+    pub adjacent: Vec<(RuneKinds, RuneKinds)>,
+}
+
+// Collected runes slotted into positions, each rune at most once
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CraftingGrid {
+    slots: Vec<Option<RuneKinds>>,
+}
+
+impl Default for CraftingGrid {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; (CRAFTING_GRID_SIZE * CRAFTING_GRID_SIZE) as usize],
+        }
+    }
+}
+
+impl CraftingGrid {
+    pub fn in_bounds(position: Point) -> bool {
+        position.x >= 0
+            && position.y >= 0
+            && position.x < CRAFTING_GRID_SIZE
+            && position.y < CRAFTING_GRID_SIZE
+    }
+
+    pub fn center() -> Point {
+        Point::new(CRAFTING_GRID_SIZE / 2, CRAFTING_GRID_SIZE / 2)
+    }
+
+    // The rune just found goes in the middle, which offers the upgrades for that rune
+    pub fn starting_with(rune: RuneKinds) -> Self {
+        let mut grid = Self::default();
+        grid.place(Self::center(), Some(rune));
+        grid
+    }
+
+    fn index(position: Point) -> usize {
+        (position.y * CRAFTING_GRID_SIZE + position.x) as usize
+    }
+
+    pub fn get(&self, position: Point) -> Option<RuneKinds> {
+        self.slots[Self::index(position)]
+    }
+
+    // Moves the rune here if it was already somewhere else on the grid
+    pub fn place(&mut self, position: Point, rune: Option<RuneKinds>) {
+        if let Some(rune) = rune {
+            for slot in self.slots.iter_mut() {
+                if *slot == Some(rune) {
+                    *slot = None;
+                }
+            }
+        }
+        self.slots[Self::index(position)] = rune;
+    }
+
+    pub fn position_of(&self, rune: RuneKinds) -> Option<Point> {
+        self.slots
+            .iter()
+            .position(|s| *s == Some(rune))
+            .map(|i| Point::new(i as i32 % CRAFTING_GRID_SIZE, i as i32 / CRAFTING_GRID_SIZE))
+    }
+
+    pub fn runes(&self) -> Vec<RuneKinds> {
+        self.slots.iter().flatten().copied().collect()
+    }
+
+    pub fn matches(&self, recipe: &Recipe) -> bool {
+        let runes = self.runes();
+        if runes.len() != recipe.runes.len() || !recipe.runes.iter().all(|r| runes.contains(r)) {
+            return false;
+        }
+        recipe.adjacent.iter().all(
+            |(a, b)| match (self.position_of(*a), self.position_of(*b)) {
+                (Some(a), Some(b)) => (a.x - b.x).abs() + (a.y - b.y).abs() == 1,
+                _ => false,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::campaign::{CraftingGrid, Recipe, RuneKinds};
+    use crate::prelude::*;
+
+    #[test]
+    fn recipes_need_exact_runes_and_adjacency() {
+        let fire_next_to_ice = Recipe {
+            runes: vec![RuneKinds::Fire, RuneKinds::Ice],
+            adjacent: vec![(RuneKinds::Fire, RuneKinds::Ice)],
+        };
+        let fire_only = Recipe {
+            runes: vec![RuneKinds::Fire],
+            adjacent: vec![],
+        };
+
+        let mut grid = CraftingGrid::default();
+        assert!(grid.matches(&Recipe::default()));
+
+        grid.place(Point::new(0, 0), Some(RuneKinds::Fire));
+        assert!(grid.matches(&fire_only));
+        assert!(!grid.matches(&Recipe::default()));
+
+        // Diagonal is not next to
+        grid.place(Point::new(1, 1), Some(RuneKinds::Ice));
+        assert!(!grid.matches(&fire_only));
+        assert!(!grid.matches(&fire_next_to_ice));
+
+        grid.place(Point::new(1, 0), Some(RuneKinds::Ice));
+        assert_eq!(grid.get(Point::new(1, 1)), None);
+        assert!(grid.matches(&fire_next_to_ice));
+    }
+}
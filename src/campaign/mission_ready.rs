@@ -1,8 +1,7 @@
 use std::collections::HashSet;
 
-use macroquad::rand::ChooseRandom;
-
 use crate::{
+    campaign::RunSetup,
     mission::{Character, MissionState},
     prelude::*,
     screens::victory::VictoryState,
@@ -49,34 +48,101 @@ pub struct CampaignState {
     pub character: Character,
     pub chosen_upgrades: HashSet<String>,
     pub collected_runes: HashSet<RuneKinds>,
+
+    // Name of the starting equipment chosen
+    #[serde(default)]
+    pub kit: String,
+    // Seconds spent in missions so far
+    #[serde(default)]
+    pub playtime: f64,
+    #[serde(default)]
+    pub save_slot: usize,
+
+    // Every random choice between missions comes from this, see campaign_rng.
+    // Runs from before seeds were saved each get one of their own
+    #[serde(default = "RunSetup::random_seed")]
+    pub seed: u64,
+    // The date, for daily challenge runs
+    #[serde(default)]
+    pub daily: Option<String>,
 }
 
+// What campaign_rng is being asked for, so each choice gets its own stream
+const RNG_RUNE_ORDER: u64 = 1;
+const RNG_MISSION: u64 = 2;
+
 impl CampaignState {
     pub fn new(character: Character) -> CampaignState {
         Self {
             character,
             chosen_upgrades: HashSet::new(),
             collected_runes: HashSet::new(),
+            kit: String::new(),
+            playtime: 0.0,
+            save_slot: 0,
+            seed: 0,
+            daily: None,
         }
     }
 
-    pub fn process_ready_for_mission(&mut self, screen: &mut Screen) -> Option<GameFlow> {
-        screen.play_random_music();
+    pub fn with_setup(character: Character, setup: &RunSetup) -> CampaignState {
+        Self {
+            seed: setup.seed,
+            daily: setup.daily.clone(),
+            ..Self::new(character)
+        }
+    }
+
+    pub fn setup(&self) -> RunSetup {
+        RunSetup {
+            seed: self.seed,
+            daily: self.daily.clone(),
+        }
+    }
+
+    // Depends only on the seed and how far along the run is, never on the upgrades
+    // picked, so two runs on the same seed see the same runes and maps in the same order
+    pub(crate) fn campaign_rng(&self, purpose: u64) -> GameRng {
+        let stream = (purpose << 32) | self.completed_mission_count() as u64;
+        GameRng::new(self.seed ^ GameRng::new(stream).rand() as u64)
+    }
+
+    pub fn rune_order(&self) -> Vec<RuneKinds> {
+        let mut runes = RuneKinds::all().to_vec();
+        let rng = GameRng::new(self.seed ^ RNG_RUNE_ORDER);
+        // Fisher-Yates, so the order only depends on our own generator
+        for i in (1..runes.len()).rev() {
+            let j = rng.gen_range(0, i as i32 + 1) as usize;
+            runes.swap(i, j);
+        }
+        runes
+    }
 
-        let runes_to_find = RuneKinds::all()
+    pub fn next_rune(&self) -> Option<RuneKinds> {
+        self.rune_order()
             .into_iter()
-            .filter(|r| !self.collected_runes.contains(r))
-            .collect::<Vec<_>>();
+            .find(|r| !self.collected_runes.contains(r))
+    }
+
+    pub fn mission_seed(&self) -> u64 {
+        let rng = self.campaign_rng(RNG_MISSION);
+        ((rng.rand() as u64) << 32) | rng.rand() as u64
+    }
 
-        let rune_to_find = runes_to_find.choose();
+    pub fn process_ready_for_mission<S: ScreenInterface>(
+        &mut self,
+        screen: &mut S,
+    ) -> Option<GameFlow> {
+        screen.play_random_music();
 
-        if let Some(rune_to_find) = rune_to_find {
-            Some(GameFlow::Gameplay(MissionState::new(
+        if let Some(rune_to_find) = self.next_rune() {
+            Some(GameFlow::Gameplay(MissionState::new_with_seed(
                 self.clone(),
-                *rune_to_find,
+                rune_to_find,
+                self.mission_seed(),
             )))
         } else {
-            Some(GameFlow::Victory(VictoryState::new()))
+            Some(GameFlow::Victory(VictoryState::new(self.setup())))
         }
     }
 
@@ -92,3 +158,36 @@ impl CampaignState {
         self.collected_runes.len() as u32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::campaign::{CampaignState, RunSetup, RuneKinds};
+    use crate::mission::Data;
+
+    fn campaign(seed: u64) -> CampaignState {
+        let player = Data::load().unwrap().get_character("Bat");
+        CampaignState::with_setup(player, &RunSetup::custom(seed))
+    }
+
+    #[test]
+    fn same_seed_same_campaign() {
+        let mut first = campaign(42);
+        let mut second = campaign(42);
+        assert_eq!(first.rune_order(), second.rune_order());
+        assert_eq!(first.rune_order().len(), RuneKinds::all().len());
+
+        // Picking different upgrades along the way changes nothing about what comes next
+        second.chosen_upgrades.insert("Something Else".to_string());
+        second.character.defense += 5;
+        for _ in RuneKinds::all() {
+            let rune = first.next_rune().unwrap();
+            assert_eq!(Some(rune), second.next_rune());
+            assert_eq!(first.mission_seed(), second.mission_seed());
+            first.collected_runes.insert(rune);
+            second.collected_runes.insert(rune);
+        }
+        assert_eq!(first.next_rune(), None);
+
+        assert_ne!(campaign(43).mission_seed(), campaign(42).mission_seed());
+    }
+}
@@ -1,18 +1,27 @@
 use crate::{
-    campaign::{select_equipment::SelectEquipmentState, upgrade::UpgradeState},
-    mission::MissionState,
+    campaign::select_equipment::SelectEquipmentState,
+    mission::{SavedGame, delete_save_slot, load_save_slot, write_save_slot},
     prelude::*,
+    screens::save_slots::{SaveSlotsMode, SaveSlotsState},
 };
 
 mod mission_ready;
 pub use mission_ready::{CampaignState, RuneKinds};
 
+mod crafting;
+pub use crafting::*;
+
+mod seed;
+pub use seed::RunSetup;
+
 mod select_equipment;
+pub use select_equipment::{EquipmentOption, load_equipment_options};
 
 mod upgrade;
+pub use upgrade::UpgradeState;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-enum CampaignStep {
+pub(crate) enum CampaignStep {
     SelectEquipment(SelectEquipmentState),
     SelectUpgrade(UpgradeState),
     MissionReady(CampaignState),
@@ -25,9 +34,9 @@ pub struct CampaignScreenState {
 }
 
 impl CampaignScreenState {
-    pub fn new() -> Self {
+    pub fn new(save_slot: usize, setup: RunSetup) -> Self {
         Self {
-            step: CampaignStep::SelectEquipment(SelectEquipmentState::new()),
+            step: CampaignStep::SelectEquipment(SelectEquipmentState::new(save_slot, setup)),
             frame: 0,
         }
     }
@@ -49,16 +58,68 @@ impl CampaignScreenState {
         }
     }
 
-    pub fn load_save() -> GameFlow {
-        match MissionState::load_from_disk() {
-            Some(state) => GameFlow::Gameplay(state),
-            None => GameFlow::Campaign(CampaignScreenState::new()),
+    pub fn load_save(save_slot: usize) -> GameFlow {
+        match load_save_slot(save_slot) {
+            Ok(SavedGame::Mission(state)) => GameFlow::Gameplay(*state),
+            Ok(SavedGame::Campaign(state)) => GameFlow::Campaign(*state),
+            Err(e) => {
+                eprintln!("Unable to load game: {e}");
+                GameFlow::SaveSlots(SaveSlotsState::with_error(
+                    SaveSlotsMode::Load,
+                    save_slot,
+                    e,
+                ))
+            }
+        }
+    }
+
+    // Before equipment is chosen there is nothing worth saving
+    pub fn campaign(&self) -> Option<&CampaignState> {
+        match &self.step {
+            CampaignStep::SelectEquipment(_) => None,
+            CampaignStep::SelectUpgrade(state) => Some(state.campaign()),
+            CampaignStep::MissionReady(state) => Some(state),
+        }
+    }
+
+    pub fn upgrade(&self) -> Option<&UpgradeState> {
+        match &self.step {
+            CampaignStep::SelectUpgrade(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    pub fn set_save_slot(&mut self, save_slot: usize) {
+        match &mut self.step {
+            CampaignStep::SelectEquipment(state) => state.save_slot = save_slot,
+            CampaignStep::SelectUpgrade(state) => state.campaign_mut().save_slot = save_slot,
+            CampaignStep::MissionReady(state) => state.save_slot = save_slot,
+        }
+    }
+
+    pub fn save_to_disk(&self) {
+        if let Some(campaign) = self.campaign() {
+            write_save_slot(campaign, SavedGame::Campaign(Box::new(self.clone())));
         }
     }
 
     pub fn process_frame(&mut self, screen: &mut Screen) -> Option<GameFlow> {
         self.frame += 1;
 
+        let next = self.process_step(screen);
+        match &next {
+            Some(GameFlow::Gameplay(mission)) => mission.save_to_disk(),
+            Some(GameFlow::Victory(_)) => {
+                if let Some(campaign) = self.campaign() {
+                    delete_save_slot(campaign.save_slot);
+                }
+            }
+            _ => {}
+        }
+        next
+    }
+
+    fn process_step(&mut self, screen: &mut Screen) -> Option<GameFlow> {
         match &mut self.step {
             CampaignStep::SelectEquipment(state) => {
                 if let Some(next_step) = state.process_frame(screen, self.frame) {
@@ -69,6 +130,7 @@ impl CampaignScreenState {
             CampaignStep::SelectUpgrade(state) => {
                 if let Some(next_step) = state.process_frame() {
                     self.step = next_step;
+                    self.save_to_disk();
                 }
                 None
             }
@@ -0,0 +1,91 @@
+use crate::prelude::*;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// How a new run gets its seed. Daily challenges share the seed (and so the
+// rune order, missions and starting kit) with everyone playing that day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSetup {
+    pub seed: u64,
+    // The date of the daily challenge, as YYYY-MM-DD
+    pub daily: Option<String>,
+}
+
+impl RunSetup {
+    pub fn random() -> Self {
+        Self::custom(Self::random_seed())
+    }
+
+    // Seeds are a full u64, so any of them can come up and not only the low half
+    pub fn random_seed() -> u64 {
+        let rng = GameRng::from_time();
+        ((rng.rand() as u64) << 32) | rng.rand() as u64
+    }
+
+    pub fn custom(seed: u64) -> Self {
+        Self { seed, daily: None }
+    }
+
+    pub fn daily() -> Self {
+        Self::daily_for_day(macroquad::miniquad::date::now() as u64 / SECONDS_PER_DAY)
+    }
+
+    // Days are counted from 1970-01-01 (UTC)
+    pub fn daily_for_day(day: u64) -> Self {
+        let rng = GameRng::new(day);
+        let seed = ((rng.rand() as u64) << 32) | rng.rand() as u64;
+        let (year, month, day) = civil_from_days(day as i64);
+        Self {
+            seed,
+            daily: Some(format!("{year:04}-{month:02}-{day:02}")),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.daily {
+            Some(date) => format!("Daily Challenge {date} - Seed {}", self.seed),
+            None => format!("Seed {}", self.seed),
+        }
+    }
+}
+
+// Howard Hinnant's days to civil date, for the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_setup_follows_date() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20744), (2026, 10, 18));
+
+        let today = RunSetup::daily_for_day(20744);
+        assert_eq!(today.daily.as_deref(), Some("2026-10-18"));
+        assert_eq!(today, RunSetup::daily_for_day(20744));
+        assert_ne!(today.seed, RunSetup::daily_for_day(20745).seed);
+    }
+
+    #[test]
+    fn random_seeds_use_the_high_bits() {
+        assert!((0..4).any(|_| RunSetup::random_seed() > u32::MAX as u64));
+    }
+}
@@ -7,16 +7,16 @@ use macroquad::{
 
 use crate::{
     Difficulty,
-    campaign::{CampaignState, CampaignStep},
-    mission::{Character, CharacterId, Data, Health, Weapon, Will},
+    campaign::{CampaignState, CampaignStep, RunSetup},
+    mission::{Character, CharacterId, Data, Faction, Health, LootTable, Weapon, Will},
     prelude::*,
 };
 
 const STARTS_JSON: &str = include_str!("../../data/starts.json");
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct EquipmentOption {
-    name: String,
+pub struct EquipmentOption {
+    pub name: String,
     description: Vec<String>,
     sprite: Point,
     weapon: Weapon,
@@ -32,58 +32,28 @@ pub struct SelectEquipmentState {
     options: Vec<EquipmentOption>,
     selection: usize,
     bounce: bool,
+    #[serde(default)]
+    pub(crate) save_slot: usize,
+    #[serde(default = "RunSetup::random")]
+    setup: RunSetup,
 }
 
-impl SelectEquipmentState {
-    pub fn new() -> Self {
-        let weapons =
-            serde_json::from_str(STARTS_JSON).expect("Unable to load equipment choice data");
-        Self {
-            selection: 0,
-            bounce: false,
-            options: weapons,
-        }
-    }
-
-    pub fn process_frame(&mut self, screen: &Screen, frame: usize) -> Option<CampaignStep> {
-        if frame % BOUNCE_FRAME == 0 {
-            self.bounce = !self.bounce;
-        }
-
-        self.draw_equipment_option(screen, 0);
-        self.draw_equipment_option(screen, 1);
-        self.draw_equipment_option(screen, 2);
-
-        if is_key_pressed(KeyCode::Down) {
-            if self.selection < 2 {
-                self.selection += 1;
-            }
-        } else if is_key_pressed(KeyCode::Up) {
-            if self.selection > 0 {
-                self.selection -= 1;
-            }
-        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
-            return Some(CampaignStep::MissionReady(CampaignState::new(
-                self.outfit_character(screen.options.difficulty),
-            )));
-        }
-        None
-    }
-
-    fn outfit_character(&self, difficulty: Difficulty) -> Character {
-        let selection = &self.options[self.selection];
+pub fn load_equipment_options() -> Vec<EquipmentOption> {
+    serde_json::from_str(STARTS_JSON).expect("Unable to load equipment choice data")
+}
 
+impl EquipmentOption {
+    pub fn outfit_character(&self, difficulty: Difficulty) -> Character {
         let data = Data::load().expect("Mission data should load");
-        let mut skills: Vec<_> = selection
+        let skills: Vec<_> = self
             .provides_skills
             .iter()
             .map(|s| data.get_skill(s))
             .collect();
-        skills.push(data.get_skill("Health Potion"));
 
-        let mut health = selection.health as i32;
-        let mut will = selection.will as i32;
-        let mut defense = selection.defense as i32;
+        let mut health = self.health as i32;
+        let mut will = self.will as i32;
+        let mut defense = self.defense as i32;
         if difficulty == Difficulty::Easy {
             health = (health as f32 * 1.5) as i32;
             will += 2;
@@ -97,14 +67,67 @@ impl SelectEquipmentState {
             ticks: TICKS_TO_ACT, // Start off ready to go, since first move is always player
             health: Health::new(health),
             will: Will::new(will),
-            base_sprite_tile: selection.sprite,
-            weapon: selection.weapon.clone(),
+            base_sprite_tile: self.sprite,
+            weapon: self.weapon.clone(),
             skills,
-            carried_items: vec![],
+            carried_items: vec![data.get_item("Health Potion")],
+            equipped: vec![],
             enemy_memory: None,
             status_effects: vec![],
             defense,
+            loot: LootTable::default(),
+            faction: Faction::Player,
+            summon_ticks: None,
+        }
+    }
+}
+
+impl SelectEquipmentState {
+    pub fn new(save_slot: usize, setup: RunSetup) -> Self {
+        let mut options = load_equipment_options();
+        // Everyone on the daily challenge starts with the same kit
+        if setup.daily.is_some() {
+            let index = GameRng::new(setup.seed).gen_range(0, options.len() as i32) as usize;
+            options = vec![options.swap_remove(index)];
+        }
+        Self {
+            selection: 0,
+            bounce: false,
+            save_slot,
+            options,
+            setup,
+        }
+    }
+
+    pub fn process_frame(&mut self, screen: &Screen, frame: usize) -> Option<CampaignStep> {
+        if frame % BOUNCE_FRAME == 0 {
+            self.bounce = !self.bounce;
         }
+
+        for index in 0..self.options.len() {
+            self.draw_equipment_option(screen, index);
+        }
+        Screen::draw_centered_text_with_color(&self.setup.describe(), 22, 150.0, GRAY, None);
+
+        if is_key_pressed(KeyCode::Down) {
+            if self.selection + 1 < self.options.len() {
+                self.selection += 1;
+            }
+        } else if is_key_pressed(KeyCode::Up) {
+            if self.selection > 0 {
+                self.selection -= 1;
+            }
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            let option = &self.options[self.selection];
+            let mut campaign = CampaignState::with_setup(
+                option.outfit_character(screen.options.difficulty),
+                &self.setup,
+            );
+            campaign.kit = option.name.clone();
+            campaign.save_slot = self.save_slot;
+            return Some(CampaignStep::MissionReady(campaign));
+        }
+        None
     }
 
     fn draw_equipment_option(&mut self, screen: &Screen, index: usize) {
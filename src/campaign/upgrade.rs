@@ -1,16 +1,14 @@
-use macroquad::{
-    rand::ChooseRandom, shapes::draw_rectangle_lines, text::draw_text, window::screen_width,
-};
+use macroquad::{shapes::draw_rectangle_lines, text::draw_text, window::screen_height};
 
 use crate::{
-    campaign::{CampaignState, CampaignStep, mission_ready::RuneKinds},
+    campaign::{CRAFTING_GRID_SIZE, CampaignState, CampaignStep, CraftingGrid, Recipe, RuneKinds},
     mission::{Data, Health, StatusEffect, Will},
     prelude::*,
 };
 
 const STARTS_JSON: &str = include_str!("../../data/upgrades.json");
 
-// This is a stub for a future crafting system
+// Crafted on the rune grid after each mission, see data/upgrades.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct UpgradeOption {
     name: String,
@@ -25,93 +23,169 @@ struct UpgradeOption {
     added_defense: u32,
     #[serde(default)]
     provides_skills: Vec<String>,
-    tags: Vec<RuneKinds>,
+    #[serde(default)]
+    provides_items: Vec<String>,
+    #[serde(default)]
+    recipe: Recipe,
     #[serde(default)]
     pub eternal_status_effects: Vec<StatusEffect>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpgradeState {
-    options: Vec<UpgradeOption>,
-
     campaign: CampaignState,
+    // Index into what the grid can craft right now
     selection: usize,
+    #[serde(default)]
+    grid: CraftingGrid,
+    #[serde(default)]
+    cursor: Point,
+    // Picking from the list of upgrades rather than moving runes around
+    #[serde(default)]
+    choosing: bool,
+}
+
+fn load_upgrades() -> Vec<UpgradeOption> {
+    serde_json::from_str(STARTS_JSON).expect("Unable to load upgrade choice data")
 }
 
 impl UpgradeState {
     pub fn new(campaign: CampaignState, rune_found: RuneKinds) -> Self {
-        let options = Self::generate_upgrade_pool(&campaign, rune_found);
         Self {
-            options,
             campaign,
             selection: 0,
+            grid: CraftingGrid::starting_with(rune_found),
+            cursor: CraftingGrid::center(),
+            choosing: false,
         }
     }
 
-    fn generate_upgrade_pool(
-        campaign: &CampaignState,
-        rune_found: RuneKinds,
-    ) -> Vec<UpgradeOption> {
-        let upgrades: Vec<UpgradeOption> =
-            serde_json::from_str(STARTS_JSON).expect("Unable to load upgrade choice data");
+    fn craftable(&self) -> Vec<UpgradeOption> {
+        load_upgrades()
+            .into_iter()
+            .filter(|u| !self.campaign.chosen_upgrades.contains(&u.name))
+            .filter(|u| self.grid.matches(&u.recipe))
+            .collect()
+    }
+
+    pub fn craftable_count(&self) -> usize {
+        self.craftable().len()
+    }
+
+    // Only runes that have been collected can go on the grid
+    pub fn place(&mut self, position: Point, rune: Option<RuneKinds>) {
+        if rune.is_none_or(|r| self.campaign.collected_runes.contains(&r)) {
+            self.grid.place(position, rune);
+            self.selection = 0;
+        }
+    }
 
-        let all_uncollected_upgrades = upgrades
+    // Empty, then each collected rune in turn, then empty again
+    fn cycle_rune(&mut self) {
+        let collected = RuneKinds::all()
             .into_iter()
-            .filter(|u| !campaign.chosen_upgrades.contains(&u.name))
+            .filter(|r| self.campaign.collected_runes.contains(r))
             .collect::<Vec<_>>();
+        let next = match self.grid.get(self.cursor) {
+            None => collected.first().copied(),
+            Some(current) => collected
+                .iter()
+                .skip_while(|r| **r != current)
+                .nth(1)
+                .copied(),
+        };
+        self.place(self.cursor, next);
+    }
 
-        // We first choose 2 upgrades based on the rune we just found
-        let mut current_rune_upgrades: Vec<_> = all_uncollected_upgrades
-            .iter()
-            .filter(|u| u.tags.contains(&rune_found))
-            .cloned()
-            .collect::<Vec<_>>()
-            .choose_multiple(2)
-            .cloned()
-            .collect();
+    pub(crate) fn process_frame(&mut self) -> Option<CampaignStep> {
+        let craftable = self.craftable();
+        Screen::draw_centered_text("Craft an Upgrade", 48, 75.0, None);
+        self.draw_grid();
+        for (index, option) in craftable.iter().enumerate() {
+            self.draw_upgrade_option(option, index);
+        }
+        if craftable.is_empty() {
+            draw_text(
+                "Nothing can be crafted from these runes",
+                360.0,
+                240.0,
+                22.0,
+                GRAY,
+            );
+        }
+        let help = if self.choosing {
+            "Up/Down - Choose  Enter - Craft  Tab - Back to Runes"
+        } else {
+            "Arrows - Move  Space - Change Rune  Enter - Choose Upgrade"
+        };
+        Screen::draw_centered_text(help, 22, screen_height() - 40.0, None);
 
-        // And then one from any rune found or the generic upgrades
-        let generic_upgrade = all_uncollected_upgrades
-            .iter()
-            .filter(|u| {
-                u.tags
-                    .iter()
-                    .all(|t| campaign.collected_runes.contains(t) && *t != rune_found)
-            })
-            .cloned()
-            .collect::<Vec<_>>()
-            .choose()
-            .cloned()
-            .expect("At least one generic upgrade available");
-
-        current_rune_upgrades.push(generic_upgrade);
-        current_rune_upgrades
-    }
-
-    pub fn process_frame(&mut self) -> Option<CampaignStep> {
-        self.draw_upgrade_option(0);
-        self.draw_upgrade_option(1);
-        self.draw_upgrade_option(2);
-
-        if is_key_pressed(KeyCode::Down) {
-            if self.selection < 2 {
-                self.selection += 1;
+        if self.choosing {
+            if is_key_pressed(KeyCode::Down) {
+                if self.selection + 1 < craftable.len() {
+                    self.selection += 1;
+                }
+            } else if is_key_pressed(KeyCode::Up) {
+                if self.selection > 0 {
+                    self.selection -= 1;
+                }
+            } else if is_key_pressed(KeyCode::Tab) || is_key_pressed(KeyCode::Escape) {
+                self.choosing = false;
+            } else if (is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter))
+                && let Some(campaign) = self.select(self.selection)
+            {
+                return Some(CampaignStep::MissionReady(campaign));
             }
-        } else if is_key_pressed(KeyCode::Up) {
-            if self.selection > 0 {
-                self.selection -= 1;
+        } else {
+            let mut direction = Point::zero();
+            if is_key_pressed(KeyCode::Up) {
+                direction = Point::new(0, -1);
+            } else if is_key_pressed(KeyCode::Down) {
+                direction = Point::new(0, 1);
+            } else if is_key_pressed(KeyCode::Left) {
+                direction = Point::new(-1, 0);
+            } else if is_key_pressed(KeyCode::Right) {
+                direction = Point::new(1, 0);
+            } else if is_key_pressed(KeyCode::Space) {
+                self.cycle_rune();
+            } else if (is_key_pressed(KeyCode::Enter)
+                || is_key_pressed(KeyCode::KpEnter)
+                || is_key_pressed(KeyCode::Tab))
+                && !craftable.is_empty()
+            {
+                self.choosing = true;
+            }
+            if CraftingGrid::in_bounds(self.cursor + direction) {
+                self.cursor = self.cursor + direction;
             }
-        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
-            self.upgrade_character();
-
-            return Some(CampaignStep::MissionReady(self.campaign.clone()));
         }
         None
     }
 
-    fn upgrade_character(&mut self) {
-        let selection = &self.options[self.selection];
+    pub fn grid(&self) -> &CraftingGrid {
+        &self.grid
+    }
+
+    pub fn cursor(&self) -> Point {
+        self.cursor
+    }
 
+    pub fn campaign(&self) -> &CampaignState {
+        &self.campaign
+    }
+
+    pub fn campaign_mut(&mut self) -> &mut CampaignState {
+        &mut self.campaign
+    }
+
+    // Crafts the upgrade at index in what the grid can make right now, None if there is no such upgrade
+    pub fn select(&mut self, index: usize) -> Option<CampaignState> {
+        let option = self.craftable().into_iter().nth(index)?;
+        self.upgrade_character(&option);
+        Some(self.campaign.clone())
+    }
+
+    fn upgrade_character(&mut self, selection: &UpgradeOption) {
         self.campaign.character.health =
             Health::new(self.campaign.character.health.max + BASE_HEALTH_INCREASE_EVERY_MISSION);
         self.campaign.character.defense += BASE_DEFENSE_INCREASE_EVERY_MISSION;
@@ -140,6 +214,15 @@ impl UpgradeState {
                 .collect();
             self.campaign.character.skills.append(&mut new_skills);
         }
+        if !selection.provides_items.is_empty() {
+            let data = Data::load().expect("Load data for upgrade");
+            for item in &selection.provides_items {
+                self.campaign
+                    .character
+                    .carried_items
+                    .push(data.get_item(item));
+            }
+        }
         for effect in &selection.eternal_status_effects {
             self.campaign.character.status_effects.push(StatusEffect {
                 name: effect.name.clone(),
@@ -151,20 +234,60 @@ impl UpgradeState {
         self.campaign.chosen_upgrades.insert(selection.name.clone());
     }
 
-    fn draw_upgrade_option(&mut self, index: usize) {
-        let option = &self.options[index];
-        let is_selected = self.selection == index;
-        let top = 200.0 + 144.0 * index as f32;
-        let left = (screen_width() - 600.0) / 2.0;
+    fn draw_grid(&self) {
+        let cell = 80.0;
+        let (left, top) = (50.0, 200.0);
+        for y in 0..CRAFTING_GRID_SIZE {
+            for x in 0..CRAFTING_GRID_SIZE {
+                let position = Point::new(x, y);
+                let (cell_left, cell_top) = (left + x as f32 * cell, top + y as f32 * cell);
+                let border_color = if position == self.cursor && !self.choosing {
+                    WHITE
+                } else {
+                    BROWN
+                };
+                draw_rectangle_lines(
+                    cell_left,
+                    cell_top,
+                    cell - 4.0,
+                    cell - 4.0,
+                    3.0,
+                    border_color,
+                );
+                if let Some(rune) = self.grid.get(position) {
+                    draw_text(
+                        &rune.to_string(),
+                        cell_left + 8.0,
+                        cell_top + 42.0,
+                        20.0,
+                        WHITE,
+                    );
+                }
+            }
+        }
+
+        let collected = RuneKinds::all()
+            .into_iter()
+            .filter(|r| self.campaign.collected_runes.contains(r))
+            .map(|r| r.to_string())
+            .collect::<Vec<_>>();
+        draw_text("Collected runes:", left, top + 270.0, 18.0, GRAY);
+        draw_text(&collected.join(", "), left, top + 290.0, 18.0, GRAY);
+    }
+
+    fn draw_upgrade_option(&self, option: &UpgradeOption, index: usize) {
+        let is_selected = self.choosing && self.selection == index;
+        let top = 200.0 + 130.0 * index as f32;
+        let left = 330.0;
 
         let border_color = if is_selected { WHITE } else { BROWN };
-        draw_rectangle_lines(left, top, 600.0, 120.0, 3.0, border_color);
-        draw_text(&option.name, left + 50.0, top + 35.0, 22.0, WHITE);
+        draw_rectangle_lines(left, top, 640.0, 120.0, 3.0, border_color);
+        draw_text(&option.name, left + 30.0, top + 35.0, 22.0, WHITE);
 
         for (i, line) in option.description.iter().enumerate() {
             draw_text(
                 line,
-                left + 50.0,
+                left + 30.0,
                 top + 60.0 + (i as f32 * 15.0),
                 18.0,
                 WHITE,
@@ -176,8 +299,12 @@ impl UpgradeState {
 #[cfg(test)]
 mod tests {
     use crate::{
-        campaign::upgrade::{STARTS_JSON, UpgradeOption},
+        campaign::{
+            CampaignState, RunSetup, RuneKinds, UpgradeState,
+            upgrade::{STARTS_JSON, UpgradeOption},
+        },
         mission::Data,
+        prelude::*,
     };
 
     #[test]
@@ -189,5 +316,60 @@ mod tests {
             .iter()
             .flat_map(|u| u.provides_skills.iter().map(|s| data.get_skill(s)))
             .collect();
+        let _items: Vec<_> = upgrades
+            .iter()
+            .flat_map(|u| u.provides_items.iter().map(|i| data.get_item(i)))
+            .collect();
+
+        // Adjacency can only be between runes the recipe puts on the grid
+        for upgrade in &upgrades {
+            let recipe = &upgrade.recipe;
+            assert!(
+                recipe
+                    .adjacent
+                    .iter()
+                    .all(|(a, b)| recipe.runes.contains(a) && recipe.runes.contains(b)),
+                "{} needs runes it does not place",
+                upgrade.name
+            );
+        }
+    }
+
+    #[test]
+    fn grid_crafts_matching_upgrades() {
+        let player = Data::load().unwrap().get_character("Bat");
+        let mut campaign = CampaignState::with_setup(player, &RunSetup::custom(7));
+        campaign.collected_runes.insert(RuneKinds::Fire);
+        campaign.collected_runes.insert(RuneKinds::Ice);
+
+        // Just the rune found
+        let mut state = UpgradeState::new(campaign, RuneKinds::Ice);
+        assert_eq!(state.craftable().len(), 3);
+        assert!(
+            state
+                .craftable()
+                .iter()
+                .all(|u| u.recipe.runes == [RuneKinds::Ice])
+        );
+
+        // Runes not collected yet can not be placed
+        state.place(Point::new(0, 1), Some(RuneKinds::Mind));
+        assert_eq!(state.craftable().len(), 3);
+
+        state.place(Point::new(0, 1), Some(RuneKinds::Fire));
+        let names = state
+            .craftable()
+            .into_iter()
+            .map(|u| u.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Frostfire Edge"]);
+
+        let damage = state.campaign().character.weapon.damage;
+        let campaign = state.select(0).unwrap();
+        assert_eq!(campaign.character.weapon.damage, damage + 3);
+        assert!(campaign.chosen_upgrades.contains("Frostfire Edge"));
+        assert_eq!(state.craftable_count(), 0);
+        assert!(state.select(0).is_none());
+        assert_eq!(state.campaign().character.weapon.damage, damage + 3);
     }
 }
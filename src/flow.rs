@@ -5,10 +5,11 @@ use macroquad::{
 
 use crate::{
     campaign::CampaignScreenState,
-    mission::MissionState,
+    mission::{MissionState, Replay, import_legacy_save},
     prelude::*,
     screens::{
         credits::process_credits_frame, death::DeathState, help::HelpState, options::OptionsState,
+        replay::ReplayState, save_slots::SaveSlotsState, seed_entry::SeedEntryState,
         title::TitleState, victory::VictoryState,
     },
 };
@@ -24,11 +25,18 @@ pub enum GameFlow {
     Quitting,
     Victory(VictoryState),
     Credits,
+    Replay(ReplayState),
+    SaveSlots(SaveSlotsState),
+    SeedEntry(SeedEntryState),
 }
 
 impl GameFlow {
     pub fn process_frame(&mut self, screen: &mut Screen) {
         if !matches!(self, GameFlow::Gameplay(_)) && is_quit_requested() {
+            // Missions save themselves on quit, campaign screens need to as well
+            if let GameFlow::Campaign(state) = self {
+                state.save_to_disk();
+            }
             *self = GameFlow::Quitting;
         }
 
@@ -41,6 +49,9 @@ impl GameFlow {
             GameFlow::Options(state) => state.process_frame(screen),
             GameFlow::Help(state) => state.process_frame(),
             GameFlow::Credits => process_credits_frame(),
+            GameFlow::Replay(state) => state.process_frame(screen),
+            GameFlow::SaveSlots(state) => state.process_frame(),
+            GameFlow::SeedEntry(state) => state.process_frame(),
             GameFlow::Quitting => return,
         };
         if let Some(next) = maybe_next {
@@ -67,7 +78,11 @@ pub async fn main() {
     screen.load().await;
 
     screen.play_music_track(0);
-    let mut flow = GameFlow::Title(TitleState::new());
+    import_legacy_save();
+    let mut flow = match requested_replay() {
+        Some(replay) => GameFlow::Replay(ReplayState::new(replay)),
+        None => GameFlow::Title(TitleState::new()),
+    };
 
     loop {
         clear_background(BLACK);
@@ -81,3 +96,11 @@ pub async fn main() {
         macroquad::window::next_frame().await
     }
 }
+
+// archivist --replay path/to/seed.replay
+fn requested_replay() -> Option<Replay> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|a| a == "--replay")?;
+    let path = args.get(index + 1)?;
+    Replay::load_from_disk(std::path::Path::new(path))
+}
@@ -1,12 +1,12 @@
-use std::path::PathBuf;
-
 use serde::{Deserialize, Serialize};
 
 pub mod campaign;
 mod flow;
 pub mod mission;
+mod rng;
 mod screen;
 pub mod screens;
+mod storage;
 mod util;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -33,53 +33,27 @@ impl Default for Options {
 }
 
 impl Options {
+    const STORAGE_KEY: &str = "options.json";
+
     pub fn load() -> Options {
-        if let Ok(text) = std::fs::read(&Self::options_path()) {
-            serde_json::from_slice(&text).unwrap_or_default()
+        if let Some(text) = storage::read(Self::STORAGE_KEY) {
+            serde_json::from_str(&text).unwrap_or_default()
         } else {
             Options::default()
         }
     }
 
-    #[cfg(feature = "desktop")]
     pub fn save(&self) {
-        let filename = Self::options_path();
-
-        match std::fs::create_dir_all(filename.parent().expect("Project dir should be longer")) {
-            Ok(()) => {
-                if let Err(e) = std::fs::write(
-                    filename,
-                    serde_json::to_string(self).expect("Unable to save options"),
-                ) {
-                    eprintln!("Unable to save options: {e:?}");
-                }
-            }
-            Err(e) => {
-                eprintln!("Unable to create options location: {e:?}");
-            }
-        }
-    }
-
-    #[cfg(not(feature = "desktop"))]
-    pub fn save(&self) {}
-
-    #[cfg(feature = "desktop")]
-    pub fn options_path() -> PathBuf {
-        let dirs = directories::ProjectDirs::from("com", "", "Archivist")
-            .expect("Unable to find project directory?");
-        let mut path = dirs.data_dir().to_path_buf();
-        path.push("options.json");
-        path
-    }
-
-    #[cfg(not(feature = "desktop"))]
-    pub fn options_path() -> PathBuf {
-        PathBuf::new()
+        storage::write(
+            Self::STORAGE_KEY,
+            &serde_json::to_string(self).expect("Unable to save options"),
+        );
     }
 }
 
 pub mod prelude {
     pub use crate::flow::*;
+    pub use crate::rng::*;
     pub use crate::screen::*;
     pub use crate::util::*;
 
@@ -116,6 +90,15 @@ pub mod prelude {
     pub const DRUNK_STAGGER_DISTANCE: u32 = 400;
     pub const DRUNK_DESIRED_FLOOR_AMOUNT: i32 = (SCREEN_WIDTH * SCREEN_HEIGHT) / 3;
 
+    pub const DLA_DESIRED_FLOOR_AMOUNT: i32 = (SCREEN_WIDTH * SCREEN_HEIGHT) / 3;
+
+    pub const BSP_SPLIT_DEPTH: u32 = 5;
+    pub const BSP_MIN_AREA_SIZE: i32 = 8;
+
+    pub const WFC_PATTERN_SIZE: usize = 3;
+    pub const WFC_MIN_FLOOR_AMOUNT: i32 = (SCREEN_WIDTH * SCREEN_HEIGHT) / 4;
+    pub const WFC_MAX_ATTEMPTS: usize = 20;
+
     pub const VISION: usize = 8;
 
     pub const STATUS_EFFECT_MIGHT_DAMAGE_BOOST: i32 = 3;
@@ -130,5 +113,12 @@ pub mod prelude {
 
     pub const DEFENSE_IGNORED_SOME_PIERCE: i32 = 2;
 
+    // Chance (0.0 - 1.0) a monster drops anything from its loot table, tougher ones drop more often
+    pub const LOOT_DROP_CHANCE_BASE: f64 = 0.05;
+    pub const LOOT_DROP_CHANCE_PER_DIFFICULTY: f64 = 0.1;
+
+    // How long summoned characters stick around before fading away
+    pub const SUMMON_DURATION_TICKS: i32 = 2000;
+
     pub const VERSION: &str = "0.23";
 }
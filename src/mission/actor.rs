@@ -8,6 +8,7 @@ use crate::prelude::*;
 pub enum CurrentActor {
     PlayerStandardAction,
     PlayerTargeting(TargetingInfo),
+    PlayerInventory(InventoryInfo),
     EnemyAction(CharacterId),
     Animation(AnimationInfo),
 }
@@ -19,11 +20,23 @@ pub enum HandleInputResponse {
 }
 
 impl CurrentActor {
-    pub fn act(&mut self, level: &mut LevelState, screen: &mut Screen) -> Option<RequestedAction> {
+    pub fn act<S: ScreenInterface>(
+        &mut self,
+        level: &mut LevelState,
+        controller: PlayerController,
+        screen: &mut S,
+    ) -> Option<RequestedAction> {
         match self {
             CurrentActor::PlayerStandardAction => {
-                let player = level.get_player();
-                let action = self.process_input_response(get_player_action(player, level, screen));
+                let response = match (controller, level.get_player()) {
+                    (PlayerController::Keyboard, Some(player)) => {
+                        get_player_action(player, level, screen)
+                    }
+                    (PlayerController::Autopilot, Some(_)) => autopilot_action(level),
+                    // Nobody left to control, the death check ends the mission
+                    (_, None) => HandleInputResponse::Action(None),
+                };
+                let action = self.process_input_response(response);
                 // When the player makes a real action clear the per-turn log
                 if action.is_some() {
                     level.turn_log.clear();
@@ -32,16 +45,29 @@ impl CurrentActor {
             }
             CurrentActor::PlayerTargeting(targeting_info) => {
                 let is_current_target_valid = Self::is_current_target_valid(targeting_info, level);
-                let response = targeting_info.handle_input(level, screen, is_current_target_valid);
+                let response = targeting_info.handle_input(
+                    level,
+                    screen.camera_origin(),
+                    is_current_target_valid,
+                );
                 self.process_input_response(response)
             }
+            CurrentActor::PlayerInventory(inventory_info) => {
+                let player = level.get_player()?;
+                let response = inventory_info.handle_input(player);
+                let action = self.process_input_response(response);
+                if action.is_some() {
+                    level.turn_log.clear();
+                }
+                action
+            }
             CurrentActor::EnemyAction(id) => {
                 let response = default_ai_action(level, *id);
                 self.process_input_response(response)
             }
             CurrentActor::Animation(animation_info) => {
                 let is_player = animation_info.is_player;
-                let response = animation_info.handle_input();
+                let response = animation_info.handle_input(screen.skip_animations());
                 let action = self.process_input_response(response);
 
                 // When the player makes a real action clear the per-turn log
@@ -68,30 +94,44 @@ impl CurrentActor {
             CurrentActor::PlayerTargeting(targeting_info) => {
                 targeting_info.render(screen, level);
             }
+            CurrentActor::PlayerInventory(inventory_info) => inventory_info.render(level),
             CurrentActor::Animation(animation_info) => animation_info.render(screen),
             _ => {}
         }
     }
 
     pub fn is_current_target_valid(targeting_info: &TargetingInfo, level: &LevelState) -> bool {
-        let character_target_target = level.find_character_at_position(targeting_info.position);
-        let valid_target =
-            character_target_target.is_some() && !character_target_target.unwrap().is_player();
+        let source = targeting_info.source_position;
+        let position = targeting_info.position;
+
+        match &targeting_info.target_skill.targeting {
+            SkillTargeting::Ranged { .. } => {
+                let valid_target = level
+                    .find_character_at_position(position)
+                    .is_some_and(|c| !level.is_player(c.id));
 
-        let within_distance = clear_line_between(
-            level,
-            targeting_info.source_position,
-            targeting_info.position,
-            targeting_info.max_range,
-        );
+                let within_distance =
+                    clear_line_between(level, source, position, targeting_info.max_range);
 
-        valid_target && within_distance
+                valid_target && within_distance
+            }
+            SkillTargeting::Blast { .. } => {
+                level.character_can_enter(position)
+                    && level.player_can_see(position)
+                    && clear_line_between(level, source, position, targeting_info.max_range)
+            }
+            targeting @ (SkillTargeting::Cone { .. } | SkillTargeting::Line { .. }) => {
+                !targeting.affected_tiles(level, source, position).is_empty()
+            }
+            SkillTargeting::Caster | SkillTargeting::PointBlank { .. } => false,
+        }
     }
 
     pub fn needs_to_wait(&self) -> bool {
         match self {
             CurrentActor::PlayerStandardAction => true,
             CurrentActor::PlayerTargeting(_) => true,
+            CurrentActor::PlayerInventory(_) => true,
             CurrentActor::Animation(_) => true,
             CurrentActor::EnemyAction(_) => false,
         }
@@ -1,7 +1,7 @@
 use crate::mission::*;
 use crate::prelude::*;
 
-use macroquad::rand::ChooseRandom;
+use adam_fov_rs::GridPoint;
 use pathfinding::prelude::bfs;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -18,12 +18,12 @@ impl EnemyMemory {
 }
 
 pub fn default_ai_action(level: &mut LevelState, id: CharacterId) -> HandleInputResponse {
-    if can_see_player(level, id) {
-        remember_last_position(level, id);
+    if let Some(target) = nearest_visible_hostile(level, id) {
+        remember_last_position(level, id, target);
         if let Some(action) = check_skill_usage(level, id) {
             action
         } else {
-            chase_attack_player(level, id)
+            chase_attack(level, id, target)
         }
     } else {
         if let Some(last_position) = remembered_last_position_to_head_to(level, id) {
@@ -34,8 +34,21 @@ pub fn default_ai_action(level: &mut LevelState, id: CharacterId) -> HandleInput
     }
 }
 
+// The closest character on another side this one can see, who it goes after
+pub fn nearest_visible_hostile(level: &LevelState, id: CharacterId) -> Option<CharacterId> {
+    let character = level.find_character(id)?;
+    let visibility = level.map.compute_visibility(character.position);
+
+    level
+        .characters
+        .iter()
+        .filter(|c| c.is_hostile_to(character) && visibility.get(c.position))
+        .min_by_key(|c| (c.position.king_dist(character.position), c.id))
+        .map(|c| c.id)
+}
+
 fn remembered_last_position_to_head_to(level: &LevelState, id: CharacterId) -> Option<Point> {
-    let enemy = level.find_character(id);
+    let enemy = level.find_character(id)?;
 
     if let Some(enemy_memory) = &enemy.enemy_memory {
         enemy_memory.last_known_position.clone()
@@ -44,17 +57,21 @@ fn remembered_last_position_to_head_to(level: &LevelState, id: CharacterId) -> O
     }
 }
 
-fn remember_last_position(level: &mut LevelState, id: CharacterId) {
-    let player_position = level.get_player().position;
-    let enemy = level.find_character_mut(id);
+fn remember_last_position(level: &mut LevelState, id: CharacterId, target: CharacterId) {
+    let Some(target_position) = level.find_character(target).map(|t| t.position) else {
+        return;
+    };
 
-    if let Some(enemy_memory) = &mut enemy.enemy_memory {
-        enemy_memory.last_known_position = Some(player_position);
+    if let Some(enemy_memory) = level
+        .find_character_mut(id)
+        .and_then(|e| e.enemy_memory.as_mut())
+    {
+        enemy_memory.last_known_position = Some(target_position);
     }
 }
 
 pub fn check_skill_usage(level: &LevelState, id: CharacterId) -> Option<HandleInputResponse> {
-    let enemy = level.find_character(id);
+    let enemy = level.find_character(id)?;
     if enemy.skills.is_empty() {
         return None;
     }
@@ -93,6 +110,16 @@ pub fn check_skill_usage(level: &LevelState, id: CharacterId) -> Option<HandleIn
                         )));
                     }
                 }
+                targeting => {
+                    if let Some(target) = best_area_target(level, enemy, skill) {
+                        return Some(targeting.area_skill_response(
+                            level,
+                            enemy,
+                            target,
+                            &skill.name,
+                        ));
+                    }
+                }
             }
         }
     }
@@ -100,14 +127,108 @@ pub fn check_skill_usage(level: &LevelState, id: CharacterId) -> Option<HandleIn
     None
 }
 
-fn wants_caster_effect(effect: &Effect, enemy: &Character) -> bool {
+// Where to aim an area skill to catch the most enemies and fewest friends, if anywhere is worth it
+pub fn best_area_target(level: &LevelState, caster: &Character, skill: &Skill) -> Option<Point> {
+    let visibility = level.map.compute_visibility(caster.position);
+    let candidates: Vec<Point> = match &skill.targeting {
+        SkillTargeting::PointBlank { .. } => vec![caster.position],
+        SkillTargeting::Blast { max_range, .. } | SkillTargeting::Line { max_range, .. } => level
+            .characters
+            .iter()
+            .filter(|c| {
+                c.id != caster.id
+                    && visibility.get(c.position)
+                    && clear_line_between(level, caster.position, c.position, *max_range)
+            })
+            .map(|c| c.position)
+            .collect(),
+        SkillTargeting::Cone { length } => level
+            .characters
+            .iter()
+            .filter(|c| {
+                c.id != caster.id
+                    && visibility.get(c.position)
+                    && c.position.king_dist(caster.position) <= *length as usize
+            })
+            .map(|c| c.position)
+            .collect(),
+        SkillTargeting::Caster | SkillTargeting::Ranged { .. } => vec![],
+    };
+
+    candidates
+        .into_iter()
+        .map(|target| {
+            let tiles = skill
+                .targeting
+                .affected_tiles(level, caster.position, target);
+            (target, area_score(level, caster, &skill.effect, &tiles))
+        })
+        .filter(|(_, score)| *score > 0)
+        .max_by_key(|(_, score)| *score)
+        .map(|(target, _)| target)
+}
+
+// Each character caught counts for or against using it, depending on
+// if the effect helps or hurts them and which side they are on
+pub fn area_score(level: &LevelState, caster: &Character, effect: &Effect, tiles: &[Point]) -> i32 {
+    tiles
+        .iter()
+        .filter_map(|p| level.find_character_at_position(*p))
+        .map(|c| {
+            let ally = !c.is_hostile_to(caster);
+            if ally {
+                effect_value(effect, c)
+            } else {
+                -effect_value(effect, c)
+            }
+        })
+        .sum()
+}
+
+// 1 if the effect would help the target, -1 if it would hurt them and 0 if it would be wasted
+pub fn effect_value(effect: &Effect, target: &Character) -> i32 {
     match effect {
-        Effect::ApplyDamage { .. } => false,
-        Effect::Heal { amount } => enemy.health.max - enemy.health.current >= *amount,
-        Effect::AddStatus { effect } => effect.is_positive(),
+        Effect::ApplyDamage { .. } => -1,
+        Effect::Heal { amount } => {
+            if target.health.max - target.health.current >= *amount {
+                1
+            } else {
+                0
+            }
+        }
+        Effect::AddStatus { effect } => {
+            if effect.is_positive() {
+                1
+            } else {
+                -1
+            }
+        }
+        Effect::Sequence(effects) => effects
+            .iter()
+            .map(|e| effect_value(e, target))
+            .sum::<i32>()
+            .signum(),
+        Effect::IfTargetHasStatus {
+            kind,
+            effect,
+            otherwise,
+        } => {
+            if target.has_status_effect(*kind) {
+                effect_value(effect, target)
+            } else {
+                otherwise.as_ref().map_or(0, |o| effect_value(o, target))
+            }
+        }
+        Effect::WithChance { effect, .. } => effect_value(effect, target),
+        // Another pair of hands is always welcome
+        Effect::Summon { .. } => 1,
     }
 }
 
+fn wants_caster_effect(effect: &Effect, enemy: &Character) -> bool {
+    effect_value(effect, enemy) > 0
+}
+
 fn find_ranged_target(
     enemy: &Character,
     effect: &Effect,
@@ -116,16 +237,21 @@ fn find_ranged_target(
 ) -> Option<(CharacterId, Point)> {
     match effect {
         Effect::ApplyDamage { .. } => {
-            let player = level.get_player();
-            if clear_line_between(level, enemy.position, player.position, max_range) {
-                Some((player.id, player.position))
-            } else {
-                None
-            }
+            let visibility = level.map.compute_visibility(enemy.position);
+            level
+                .characters
+                .iter()
+                .filter(|c| {
+                    c.is_hostile_to(enemy)
+                        && visibility.get(c.position)
+                        && clear_line_between(level, enemy.position, c.position, max_range)
+                })
+                .min_by_key(|c| (c.position.king_dist(enemy.position), c.id))
+                .map(|c| (c.id, c.position))
         }
         Effect::Heal { amount } => {
             for character in &level.characters {
-                if !character.is_player()
+                if !character.is_hostile_to(enemy)
                     && clear_line_between(level, enemy.position, character.position, max_range)
                     && character.health.max - character.health.current >= *amount
                 {
@@ -141,19 +267,20 @@ fn find_ranged_target(
                 if clear_line_between(level, enemy.position, character.position, max_range)
                     && visibility.get(character.position)
                 {
-                    let is_negative_and_targets_player =
-                        character.is_player() && !effect.is_positive();
+                    let is_negative_and_targets_hostile =
+                        character.is_hostile_to(enemy) && !effect.is_positive();
                     let is_positive_and_targets_ally =
-                        !character.is_player() && effect.is_positive();
+                        !character.is_hostile_to(enemy) && effect.is_positive();
                     let target_has_status_already = character.has_status_effect(effect.kind);
 
                     // Only use a status if they:
                     // - Already don't have it AND
-                    // - It is bad and we can target the player
-                    // - It is good and we can target the character and can see the player (so not used too early)
+                    // - It is bad and we can target a hostile
+                    // - It is good and we can target an ally and can see a hostile (so not used too early)
                     if !target_has_status_already
-                        && (is_negative_and_targets_player
-                            || (is_positive_and_targets_ally && can_see_player(level, enemy.id)))
+                        && (is_negative_and_targets_hostile
+                            || (is_positive_and_targets_ally
+                                && nearest_visible_hostile(level, enemy.id).is_some()))
                     {
                         return Some((character.id, character.position));
                     }
@@ -161,17 +288,55 @@ fn find_ranged_target(
             }
             None
         }
+        // Whatever the first part wants to hit decides the target
+        Effect::Sequence(effects) => effects
+            .iter()
+            .find_map(|e| find_ranged_target(enemy, e, max_range, level)),
+        Effect::IfTargetHasStatus {
+            kind,
+            effect,
+            otherwise,
+        } => find_ranged_target(enemy, effect, max_range, level)
+            .filter(|(id, _)| {
+                level
+                    .find_character(*id)
+                    .is_some_and(|c| c.has_status_effect(*kind))
+            })
+            .or_else(|| {
+                otherwise.as_ref().and_then(|o| {
+                    find_ranged_target(enemy, o, max_range, level).filter(|(id, _)| {
+                        level
+                            .find_character(*id)
+                            .is_some_and(|c| !c.has_status_effect(*kind))
+                    })
+                })
+            }),
+        Effect::WithChance { effect, .. } => find_ranged_target(enemy, effect, max_range, level),
+        // Called up beside the caster instead
+        Effect::Summon { .. } => None,
     }
 }
 
-pub fn chase_attack_player(level: &mut LevelState, id: CharacterId) -> HandleInputResponse {
-    let enemy = level.find_character(id);
-    let player = level.get_player();
+pub fn chase_attack(
+    level: &mut LevelState,
+    id: CharacterId,
+    target: CharacterId,
+) -> HandleInputResponse {
+    let (Some(enemy), Some(target)) = (level.find_character(id), level.find_character(target))
+    else {
+        return wander_action(level, id);
+    };
 
     let path = bfs(
         &enemy.position,
-        |p| adjacent_squares(level, *p, PathCharacterOptions::AllowEmptyOrPlayer),
-        |p| *p == player.position,
+        |p| {
+            adjacent_squares(
+                level,
+                *p,
+                PathCharacterOptions::AllowEmptyOrHostile(enemy.faction),
+            )
+        },
+        |p| *p == target.position,
     );
     if let Some(path) = path {
         // First position on path is current
@@ -187,11 +352,20 @@ pub fn head_to_position_action(
     id: CharacterId,
     position: Point,
 ) -> HandleInputResponse {
-    let enemy_position = level.find_character(id).position;
+    let Some(enemy) = level.find_character(id) else {
+        return wait_action(id);
+    };
+    let (enemy_position, faction) = (enemy.position, enemy.faction);
 
     let path = bfs(
         &enemy_position,
-        |p| adjacent_squares(level, *p, PathCharacterOptions::AllowEmptyOrPlayer),
+        |p| {
+            adjacent_squares(
+                level,
+                *p,
+                PathCharacterOptions::AllowEmptyOrHostile(faction),
+            )
+        },
         |p| *p == position,
     );
 
@@ -205,23 +379,32 @@ pub fn head_to_position_action(
 }
 
 pub fn wander_action(level: &mut LevelState, id: CharacterId) -> HandleInputResponse {
-    let enemy = level.find_character(id);
+    let Some(enemy) = level.find_character(id) else {
+        return wait_action(id);
+    };
     let options = adjacent_squares(
         level,
         enemy.position,
-        PathCharacterOptions::AllowEmptyOrPlayer,
+        PathCharacterOptions::AllowEmptyOrHostile(enemy.faction),
     );
-    let selection = options.choose();
+    let selection = level.rng.choose(&options);
     match selection {
         Some(position) => move_to(level, id, *position),
-        None => HandleInputResponse::Action(Some(RequestedAction::Wait(id))),
+        None => wait_action(id),
     }
 }
 
+// Also what anyone no longer around does, which passes the turn on
+fn wait_action(id: CharacterId) -> HandleInputResponse {
+    HandleInputResponse::Action(Some(RequestedAction::Wait(id)))
+}
+
 fn move_to(level: &mut LevelState, id: CharacterId, position: Point) -> HandleInputResponse {
-    let enemy = level.find_character_mut(id);
     // If we move onto the last known position of the enemy, clear our memory
-    if let Some(enemy_memory) = &mut enemy.enemy_memory {
+    if let Some(enemy_memory) = level
+        .find_character_mut(id)
+        .and_then(|e| e.enemy_memory.as_mut())
+    {
         if enemy_memory.last_known_position == Some(position) {
             enemy_memory.last_known_position = None;
         }
@@ -238,7 +421,8 @@ mod tests {
     fn chases_player() {
         let (id, mut level) = create_test_map();
 
-        let action = chase_attack_player(&mut level, id);
+        let player = level.get_player().unwrap().id;
+        let action = chase_attack(&mut level, id, player);
         assert_eq!(
             action,
             HandleInputResponse::Action(Some(RequestedAction::Move(id, Point::new(1, 4))))
@@ -255,10 +439,10 @@ mod tests {
             action,
             HandleInputResponse::Action(Some(RequestedAction::Move(id, Point::new(1, 4))))
         );
-        level.find_character_mut(id).position = Point::new(1, 4);
+        level.find_character_mut(id).unwrap().position = Point::new(1, 4);
 
         // Teleport the player away, far enough we can't be seen now
-        level.get_player_mut().position = Point::new(40, 40);
+        level.get_player_mut().unwrap().position = Point::new(40, 40);
 
         // We should keep heading to the players last position
         for i in 0..3 {
@@ -267,13 +451,14 @@ mod tests {
                 action,
                 HandleInputResponse::Action(Some(RequestedAction::Move(id, Point::new(1, 3 - i))))
             );
-            level.find_character_mut(id).position = Point::new(1, 3 - i);
+            level.find_character_mut(id).unwrap().position = Point::new(1, 3 - i);
         }
 
         // And then have forgotten
         assert!(
             level
                 .find_character(id)
+                .unwrap()
                 .enemy_memory
                 .as_ref()
                 .unwrap()
@@ -281,4 +466,67 @@ mod tests {
                 .is_none()
         );
     }
+
+    #[test]
+    fn area_skill_avoids_allies() {
+        let (id, mut level) = create_test_map();
+        let blast = Skill {
+            name: "Blast".to_string(),
+            cost: SkillCost::None,
+            effect: Effect::ApplyDamage {
+                damage: 5,
+                on_hit: None,
+                on_hit_self: None,
+                pierce: DamagePierce::None,
+            },
+            targeting: SkillTargeting::Blast {
+                max_range: 6,
+                radius: 1,
+                sprite: AnimationSpriteKind::SingleFrame(Point::zero()),
+            },
+        };
+
+        let enemy = level.find_character(id).unwrap().clone();
+        assert_eq!(
+            best_area_target(&level, &enemy, &blast),
+            Some(Point::new(1, 1))
+        );
+
+        // Any blast that hits the player now hits a friend too
+        let mut friend = Data::load().unwrap().get_character("Bat");
+        friend.position = Point::new(1, 2);
+        level.characters.push(friend);
+        assert_eq!(best_area_target(&level, &enemy, &blast), None);
+    }
+
+    #[test]
+    fn goes_after_nearest_hostile_faction() {
+        let (id, mut level) = create_test_map();
+        let player = level.get_player().unwrap().id;
+        assert_eq!(nearest_visible_hostile(&level, id), Some(player));
+
+        // A summoned wolf in the way is closer, and fights back
+        let mut wolf = Data::load().unwrap().get_character("Wolf");
+        wolf.position = Point::new(1, 4);
+        wolf.faction = Faction::Player;
+        let wolf_id = wolf.id;
+        level.characters.push(wolf);
+        assert_eq!(nearest_visible_hostile(&level, id), Some(wolf_id));
+        assert_eq!(nearest_visible_hostile(&level, wolf_id), Some(id));
+
+        let weapon = level.find_character(id).unwrap().weapon.clone();
+        assert_eq!(
+            default_ai_action(&mut level, id),
+            HandleInputResponse::Action(Some(RequestedAction::WeaponAttack {
+                source: id,
+                target: wolf_id,
+                weapon,
+            }))
+        );
+
+        // Another monster is only a friend in the way
+        level.find_character_mut(wolf_id).unwrap().faction = Faction::Monsters;
+        assert_eq!(nearest_visible_hostile(&level, id), Some(player));
+        assert_eq!(nearest_visible_hostile(&level, wolf_id), Some(player));
+    }
 }
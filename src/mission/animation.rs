@@ -82,7 +82,7 @@ impl AnimationInfo {
         }
     }
 
-    pub fn handle_input(&mut self) -> HandleInputResponse {
+    pub fn handle_input(&mut self, skip: bool) -> HandleInputResponse {
         self.ticks -= 1;
         if self.ticks == 0 {
             self.path.remove(0);
@@ -90,7 +90,7 @@ impl AnimationInfo {
             self.ticks = ANIMATION_TICKS_PER_TILE;
         }
 
-        if is_key_pressed(KeyCode::Escape) {
+        if skip || is_key_pressed(KeyCode::Escape) {
             self.path.clear();
         }
 
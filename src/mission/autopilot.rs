@@ -0,0 +1,315 @@
+use crate::mission::*;
+use crate::prelude::*;
+
+use adam_fov_rs::GridPoint;
+use pathfinding::prelude::bfs;
+
+const AUTOPILOT_REST_HEALTH_PERCENTAGE: f32 = 0.75;
+const AUTOPILOT_HEAL_HEALTH_PERCENTAGE: f32 = 0.5;
+
+// Who decides what the player does on their turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlayerController {
+    #[default]
+    Keyboard,
+    Autopilot,
+}
+
+// Plays the mission without input: fight anything in sight, otherwise
+// fetch the Runestone (exploring and heading down until it is found) and head back up the stairs
+pub fn autopilot_action(level: &LevelState) -> HandleInputResponse {
+    let Some(player) = level.get_player() else {
+        return HandleInputResponse::Action(None);
+    };
+
+    if let Some(enemy) = nearest_visible_enemy(level, player) {
+        if let Some(response) = autopilot_item_usage(player, true) {
+            response
+        } else if let Some(response) = autopilot_skill_usage(level, player, enemy) {
+            response
+        } else {
+            head_towards(level, player, enemy.position)
+        }
+    } else if let Some(response) = autopilot_item_usage(player, false) {
+        response
+    } else if player.carried_items.iter().any(|i| i.name == "Runestone") {
+        match find_known_tile(level, TileKind::Exit) {
+            Some(exit) if exit == player.position => {
+                HandleInputResponse::Action(Some(RequestedAction::Stairs))
+            }
+            Some(exit) => head_towards(level, player, exit),
+            None => wait(player),
+        }
+    } else if player.health.percentage() < AUTOPILOT_REST_HEALTH_PERCENTAGE {
+        wait(player)
+    } else if let Some(rune_position) = known_runestone_position(level) {
+        head_towards(level, player, rune_position)
+    } else if let Some(stairs) = find_known_tile(level, TileKind::StairsDown) {
+        // The Runestone is only ever on the deepest floor
+        if stairs == player.position {
+            HandleInputResponse::Action(Some(RequestedAction::Stairs))
+        } else {
+            head_towards(level, player, stairs)
+        }
+    } else if let Some(frontier) = nearest_frontier(level, player) {
+        head_towards(level, player, frontier)
+    } else if let Some(item) = nearest_reachable_item(level, player) {
+        // Everything reachable is explored, so pick up what is lying around
+        head_towards(level, player, item)
+    } else {
+        wait(player)
+    }
+}
+
+fn nearest_visible_enemy<'a>(level: &'a LevelState, player: &Character) -> Option<&'a Character> {
+    let visibility = level.map.compute_visibility(player.position);
+
+    level
+        .characters
+        .iter()
+        .filter(|c| c.is_hostile_to(player) && visibility.get(c.position))
+        .min_by_key(|c| c.position.king_dist(player.position))
+}
+
+// Whether using something on the player right now does them any good
+fn wanted_on_self(effect: &Effect, player: &Character) -> bool {
+    match effect {
+        Effect::ApplyDamage { .. } => false,
+        Effect::Heal { amount } => {
+            player.health.percentage() < AUTOPILOT_HEAL_HEALTH_PERCENTAGE
+                && player.health.max - player.health.current >= *amount
+        }
+        Effect::AddStatus { effect } => {
+            effect.is_positive() && !player.has_status_effect(effect.kind)
+        }
+        _ => effect_value(effect, player) > 0,
+    }
+}
+
+// Drinks potions in a fight, and puts on anything found for an empty slot when not
+fn autopilot_item_usage(player: &Character, in_fight: bool) -> Option<HandleInputResponse> {
+    let index = player.carried_items.iter().position(|item| {
+        if in_fight {
+            item.on_use
+                .as_ref()
+                .is_some_and(|effect| wanted_on_self(effect, player))
+        } else {
+            item.equip.as_ref().is_some_and(|equipment| {
+                !player
+                    .equipped
+                    .iter()
+                    .any(|e| e.equip.as_ref().is_some_and(|e| e.slot == equipment.slot))
+            })
+        }
+    })?;
+    let action = if in_fight {
+        RequestedAction::UseItem {
+            source: player.id,
+            index,
+        }
+    } else {
+        RequestedAction::EquipItem {
+            source: player.id,
+            index,
+        }
+    };
+    Some(HandleInputResponse::Action(Some(action)))
+}
+
+fn autopilot_skill_usage(
+    level: &LevelState,
+    player: &Character,
+    enemy: &Character,
+) -> Option<HandleInputResponse> {
+    for skill in player.skills.iter().filter(|s| s.cost.can_pay(player)) {
+        let use_skill = |target: CharacterId| RequestedAction::UseSkill {
+            source: player.id,
+            target,
+            skill_name: skill.name.clone(),
+        };
+
+        match &skill.targeting {
+            SkillTargeting::Caster => {
+                if wanted_on_self(&skill.effect, player) {
+                    return Some(HandleInputResponse::Action(Some(use_skill(player.id))));
+                }
+            }
+            SkillTargeting::Ranged { max_range, sprite } => {
+                let wanted = match &skill.effect {
+                    Effect::ApplyDamage { .. } => true,
+                    Effect::Heal { .. } => false,
+                    Effect::AddStatus { effect } => {
+                        !effect.is_positive() && !enemy.has_status_effect(effect.kind)
+                    }
+                    _ => effect_value(&skill.effect, enemy) < 0,
+                };
+                if wanted && clear_line_between(level, player.position, enemy.position, *max_range)
+                {
+                    return Some(HandleInputResponse::ChangeActor(CurrentActor::Animation(
+                        AnimationInfo::new(
+                            player.position,
+                            enemy.position,
+                            level,
+                            sprite.clone(),
+                            use_skill(enemy.id),
+                            true,
+                        ),
+                    )));
+                }
+            }
+            targeting => {
+                if let Some(target) = best_area_target(level, player, skill) {
+                    return Some(targeting.area_skill_response(level, player, target, &skill.name));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn head_towards(level: &LevelState, player: &Character, position: Point) -> HandleInputResponse {
+    match path_between_points(player.position, position, level, PathCharacterOptions::All) {
+        // First position on path is current
+        Some(path) if path.len() > 1 => {
+            HandleInputResponse::Action(Some(handle_move_bump(player, path[1], level)))
+        }
+        _ => wait(player),
+    }
+}
+
+fn wait(player: &Character) -> HandleInputResponse {
+    HandleInputResponse::Action(Some(RequestedAction::Wait(player.id)))
+}
+
+fn find_known_tile(level: &LevelState, kind: TileKind) -> Option<Point> {
+    level.map.tiles().into_iter().find(|p| {
+        let tile = level.map.get(*p);
+        tile.kind == kind && tile.known
+    })
+}
+
+fn known_runestone_position(level: &LevelState) -> Option<Point> {
+    level
+        .items
+        .iter()
+        .find(|(position, item)| item.name == "Runestone" && level.map.get(*position).known)
+        .map(|(position, _)| *position)
+}
+
+// The closest known tile we can walk to that is next to one we have not seen yet
+fn nearest_frontier(level: &LevelState, player: &Character) -> Option<Point> {
+    let is_frontier = |p: &Point| {
+        p.adjacent()
+            .into_iter()
+            .any(|a| level.map.in_bounds(a) && !level.map.get(a).known)
+    };
+
+    bfs(
+        &player.position,
+        |p| {
+            adjacent_squares(level, *p, PathCharacterOptions::All)
+                .into_iter()
+                .filter(|a| level.map.get(*a).known)
+                .collect::<Vec<_>>()
+        },
+        is_frontier,
+    )
+    .and_then(|path| path.last().copied())
+}
+
+// Locked doors can not be walked through, so loot sealed in a vault is passed over
+fn nearest_reachable_item(level: &LevelState, player: &Character) -> Option<Point> {
+    bfs(
+        &player.position,
+        |p| adjacent_squares(level, *p, PathCharacterOptions::All),
+        |p| level.items.iter().any(|(position, _)| position == p),
+    )
+    .and_then(|path| path.last().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn chases_visible_enemy() {
+        let (_, level) = create_test_map();
+        let player_id = level.get_player().unwrap().id;
+
+        assert_eq!(
+            autopilot_action(&level),
+            HandleInputResponse::Action(Some(RequestedAction::Move(player_id, Point::new(1, 2))))
+        );
+    }
+
+    #[test]
+    fn returns_runestone_to_stairs() {
+        let (id, mut level) = create_test_map();
+        level.remove_character(id);
+
+        let data = Data::load().unwrap();
+        level
+            .get_player_mut()
+            .unwrap()
+            .carried_items
+            .push(data.get_item("Runestone"));
+        level.map.set(
+            Point::new(1, 3),
+            MapTile {
+                kind: TileKind::Exit,
+                known: true,
+                variation: 0,
+            },
+        );
+        let player_id = level.get_player().unwrap().id;
+
+        assert_eq!(
+            autopilot_action(&level),
+            HandleInputResponse::Action(Some(RequestedAction::Move(player_id, Point::new(1, 2))))
+        );
+
+        level.get_player_mut().unwrap().position = Point::new(1, 3);
+        assert_eq!(
+            autopilot_action(&level),
+            HandleInputResponse::Action(Some(RequestedAction::Stairs))
+        );
+    }
+
+    #[test]
+    fn passes_over_loot_behind_locked_doors() {
+        let (id, mut level) = create_test_map();
+        level.remove_character(id);
+        for position in level.map.tiles() {
+            let mut tile = level.map.get(position);
+            tile.known = true;
+            level.map.set(position, tile);
+        }
+        level.map.set(
+            Point::new(1, 3),
+            MapTile {
+                kind: TileKind::LockedDoor,
+                known: true,
+                variation: 0,
+            },
+        );
+        let data = Data::load().unwrap();
+        level
+            .items
+            .push((Point::new(1, 5), data.get_item("Healing Draught")));
+        let player_id = level.get_player().unwrap().id;
+
+        assert_eq!(
+            autopilot_action(&level),
+            HandleInputResponse::Action(Some(RequestedAction::Wait(player_id)))
+        );
+
+        level
+            .items
+            .push((Point::new(1, 2), data.get_item("Healing Draught")));
+        assert_eq!(
+            autopilot_action(&level),
+            HandleInputResponse::Action(Some(RequestedAction::Move(player_id, Point::new(1, 2))))
+        );
+    }
+}
@@ -5,14 +5,28 @@ use macroquad::shapes::draw_rectangle;
 use crate::mission::*;
 use crate::prelude::*;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct CharacterId(u32);
 
 impl CharacterId {
+    // The counter starts over with every run of the game, so characters put on a
+    // level take their id from LevelState::add_character instead
     pub fn next() -> Self {
         static NEXT_ID: AtomicU32 = AtomicU32::new(1);
         CharacterId(NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
     }
+
+    pub fn after(self) -> Self {
+        CharacterId(self.0 + 1)
+    }
+}
+
+// Which side a character fights on, anyone on another side is fair game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Faction {
+    Player,
+    #[default]
+    Monsters,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,13 +41,23 @@ pub struct Character {
     pub weapon: Weapon,
     pub skills: Vec<Skill>,
     pub carried_items: Vec<Item>,
+    // At most one per slot, their bonuses are already added to weapon and defense
+    #[serde(default)]
+    pub equipped: Vec<Item>,
     pub enemy_memory: Option<EnemyMemory>,
     pub status_effects: Vec<StatusEffect>,
     pub defense: i32,
+    #[serde(default)]
+    pub loot: LootTable,
+    #[serde(default)]
+    pub faction: Faction,
+    // Ticks left before a summoned character fades away, None if it stays
+    #[serde(default)]
+    pub summon_ticks: Option<i32>,
 }
 
 impl Character {
-    pub fn render(&self, screen: &Screen) {
+    pub fn render(&self, screen: &Screen, show_health: bool) {
         screen.draw_sprite(
             TileSet::Creatures,
             self.position,
@@ -41,7 +65,7 @@ impl Character {
         );
 
         let health_percentage = self.health.percentage();
-        if !self.is_player() && health_percentage < 1.0 {
+        if show_health && health_percentage < 1.0 {
             draw_rectangle(
                 (self.position.x - screen.camera.left_x) as f32 * 24.0,
                 (self.position.y - screen.camera.top_y) as f32 * 24.0,
@@ -60,11 +84,48 @@ impl Character {
         tile
     }
 
-    pub fn is_player(&self) -> bool {
-        self.name == "Player"
+    pub fn is_hostile_to(&self, other: &Character) -> bool {
+        self.faction != other.faction
     }
 
     pub fn has_status_effect(&self, kind: StatusEffectKind) -> bool {
         self.status_effects.iter().any(|s| s.kind == kind)
     }
+
+    // Swaps out whatever was worn in the same slot
+    pub fn equip(&mut self, index: usize) {
+        let Some(slot) = self
+            .carried_items
+            .get(index)
+            .and_then(|i| i.equip.as_ref())
+            .map(|e| e.slot)
+        else {
+            return;
+        };
+        let item = self.carried_items.remove(index);
+        if let Some(worn) = self
+            .equipped
+            .iter()
+            .position(|i| i.equip.as_ref().is_some_and(|e| e.slot == slot))
+        {
+            self.unequip(worn);
+        }
+        self.apply_equipment(&item, 1);
+        self.equipped.push(item);
+    }
+
+    pub fn unequip(&mut self, index: usize) {
+        if index < self.equipped.len() {
+            let item = self.equipped.remove(index);
+            self.apply_equipment(&item, -1);
+            self.carried_items.push(item);
+        }
+    }
+
+    fn apply_equipment(&mut self, item: &Item, direction: i32) {
+        if let Some(equipment) = &item.equip {
+            self.weapon.damage += equipment.damage * direction;
+            self.defense += equipment.defense * direction;
+        }
+    }
 }
@@ -4,6 +4,14 @@ use crate::prelude::*;
 const CHARACTERS_JSON: &str = include_str!("../../data/characters.json");
 const SKILLS_JSON: &str = include_str!("../../data/skills.json");
 const ITEMS_JSON: &str = include_str!("../../data/items.json");
+const PREFABS_JSON: &str = include_str!("../../data/prefabs.json");
+
+// Heavier weights come up more often
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LootEntry {
+    pub item: String,
+    pub weight: u32,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CharacterInfo {
@@ -23,12 +31,15 @@ pub struct CharacterInfo {
     pub defense: u32,
     #[serde(default)]
     pub eternal_status_effects: Vec<StatusEffect>,
+    #[serde(default)]
+    pub loot: Vec<LootEntry>,
 }
 
 pub struct Data {
     characters: Vec<CharacterInfo>,
     skills: Vec<Skill>,
     items: Vec<Item>,
+    prefabs: Vec<Prefab>,
 }
 
 impl Data {
@@ -36,13 +47,21 @@ impl Data {
         let skills = serde_json::from_str(SKILLS_JSON)?;
         let characters = serde_json::from_str(CHARACTERS_JSON)?;
         let items = serde_json::from_str(ITEMS_JSON)?;
+        let prefabs = serde_json::from_str(PREFABS_JSON)?;
         Ok(Self {
             skills,
             characters,
             items,
+            prefabs,
         })
     }
 
+    // Parsed on first use, for engine code that looks data up in the middle of a turn
+    pub fn shared() -> &'static Data {
+        static DATA: std::sync::OnceLock<Data> = std::sync::OnceLock::new();
+        DATA.get_or_init(|| Data::load().expect("Able to load data"))
+    }
+
     pub fn get_character_info(&self, name: &str) -> CharacterInfo {
         self.characters
             .iter()
@@ -64,6 +83,12 @@ impl Data {
             None
         };
 
+        let difficulty = character_info.difficulty.unwrap_or(0) as f64;
+        let loot = LootTable {
+            drop_chance: LOOT_DROP_CHANCE_BASE + LOOT_DROP_CHANCE_PER_DIFFICULTY * difficulty,
+            items: character_info.loot.clone(),
+        };
+
         Character {
             name: character_info.name.clone(),
             position: Point::zero(),
@@ -75,9 +100,13 @@ impl Data {
             weapon: character_info.weapon.clone(),
             skills: character_info.skills.clone(),
             carried_items: vec![],
+            equipped: vec![],
             enemy_memory,
             status_effects: character_info.eternal_status_effects.clone(),
             defense: character_info.defense as i32,
+            loot,
+            faction: Faction::Monsters,
+            summon_ticks: None,
         }
     }
 
@@ -90,11 +119,20 @@ impl Data {
     }
 
     pub fn get_item(&self, name: &str) -> Item {
-        self.items
-            .iter()
-            .find(|i| i.name == name)
+        self.find_item(name)
             .expect(&format!("Unable to find item: {}", name))
-            .clone()
+    }
+
+    pub fn find_item(&self, name: &str) -> Option<Item> {
+        self.items.iter().find(|i| i.name == name).cloned()
+    }
+
+    pub fn get_prefabs(&self, difficulty: u32) -> Vec<Prefab> {
+        self.prefabs
+            .iter()
+            .filter(|p| p.min_difficulty <= difficulty)
+            .cloned()
+            .collect()
     }
 
     pub fn get_all_enemies(&self) -> Vec<String> {
@@ -122,4 +160,29 @@ mod tests {
             let _ = data.get_character(&name);
         }
     }
+
+    #[test]
+    fn loot_tables_scale_with_difficulty() {
+        let data = Data::load().unwrap();
+        let goblin = data.get_character("Goblin Warrior");
+        let king = data.get_character("Goblin King");
+        assert!(!goblin.loot.items.is_empty());
+        assert!(king.loot.drop_chance > goblin.loot.drop_chance);
+        assert!(data.get_character("Bat").loot.items.is_empty());
+    }
+
+    #[test]
+    fn every_loot_item_exists() {
+        let data = Data::load().unwrap();
+        for info in &data.characters {
+            for entry in &info.loot {
+                assert!(
+                    data.find_item(&entry.item).is_some(),
+                    "{} drops unknown item {}",
+                    info.name,
+                    entry.item
+                );
+            }
+        }
+    }
 }
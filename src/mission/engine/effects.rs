@@ -1,6 +1,3 @@
-use macroquad::rand::ChooseRandom;
-use macroquad::rand::gen_range;
-
 use crate::mission::*;
 use crate::prelude::*;
 
@@ -24,25 +21,98 @@ pub enum Effect {
     Heal {
         amount: i32,
     },
+    // Each effect is applied to the same target in order
+    Sequence(Vec<Effect>),
+    // Picks the effect based on a status the target has when it lands
+    IfTargetHasStatus {
+        kind: StatusEffectKind,
+        effect: Box<Effect>,
+
+        #[serde(default)]
+        otherwise: Option<Box<Effect>>,
+    },
+    // Percent chance (0 - 100) the effect happens at all
+    WithChance {
+        percent: u32,
+        effect: Box<Effect>,
+    },
+    // Calls a character next to the target, fighting on the source's side for a while
+    Summon {
+        character: String,
+    },
 }
 
-pub fn move_character(state: &mut MissionState, id: CharacterId, dest: Point, screen: &mut Screen) {
-    if state.level.find_character_at_position(dest).is_none() && state.level.map.can_enter(dest) {
-        let actor = state.level.find_character_mut(id);
-        let has_quick = actor.has_status_effect(StatusEffectKind::Quick);
-        let has_slow = actor.has_status_effect(StatusEffectKind::Slow);
+impl Effect {
+    // Sound of the first effect that will (possibly) happen
+    pub fn sound(&self) -> &'static str {
+        match self {
+            Effect::ApplyDamage { .. } => "curse",
+            Effect::AddStatus { .. } => "swing",
+            Effect::Heal { .. } | Effect::Summon { .. } => "drip",
+            Effect::Sequence(effects) => effects.first().map_or("swing", |e| e.sound()),
+            Effect::IfTargetHasStatus { effect, .. } | Effect::WithChance { effect, .. } => {
+                effect.sound()
+            }
+        }
+    }
+}
+
+pub fn move_character<S: ScreenInterface>(
+    state: &mut MissionState,
+    id: CharacterId,
+    dest: Point,
+    screen: &mut S,
+) {
+    match state.level.map.get(dest).kind {
+        TileKind::Door { open: false } => {
+            open_door(state, id, dest);
+            return;
+        }
+        TileKind::LockedDoor => {
+            unlock_door(state, id, dest, screen);
+            return;
+        }
+        _ => {}
+    }
+
+    let Some(actor) = state.level.find_character(id) else {
+        return;
+    };
+    let origin = actor.position;
+    let rooted = actor.has_status_effect(StatusEffectKind::Rooted);
+    let has_quick = actor.has_status_effect(StatusEffectKind::Quick);
+    let has_slow = actor.has_status_effect(StatusEffectKind::Slow);
+
+    // Allies trade places rather than block each other
+    let occupant = state
+        .level
+        .find_character_at_position(dest)
+        .map(|c| (c.id, c.is_hostile_to(actor)));
+    let ally_in_the_way = match occupant {
+        Some((ally, false)) => Some(ally),
+        _ => None,
+    };
 
-        let skip_move = actor.has_status_effect(StatusEffectKind::Rooted)
-            && gen_range(0.0, 1.0) < STATUS_EFFECT_CHANCE_ROOT_STAY_STILL;
+    if (occupant.is_none() || ally_in_the_way.is_some()) && state.level.map.can_enter(dest) {
+        let skip_move = rooted
+            && state
+                .level
+                .rng
+                .gen_chance(STATUS_EFFECT_CHANCE_ROOT_STAY_STILL);
 
         if !skip_move {
-            actor.position = dest;
-            if actor.is_player() {
+            if let Some(ally) = ally_in_the_way.and_then(|a| state.level.find_character_mut(a)) {
+                ally.position = origin;
+            }
+            if let Some(actor) = state.level.find_character_mut(id) {
+                actor.position = dest;
+            }
+            if state.level.is_player(id) {
                 state.level.update_visibility();
                 pickup_any_items(state, id, dest, screen);
             }
-        } else {
-            let log = format!("{} was unable to move", actor.name.clone());
+        } else if let Some(actor) = state.level.find_character(id) {
+            let log = format!("{} was unable to move", actor.name);
             state.level.push_turn_log(log);
         }
 
@@ -57,7 +127,44 @@ pub fn move_character(state: &mut MissionState, id: CharacterId, dest: Point, sc
     }
 }
 
-fn pickup_any_items(state: &mut MissionState, id: CharacterId, dest: Point, screen: &mut Screen) {
+fn open_door(state: &mut MissionState, id: CharacterId, position: Point) {
+    let mut tile = state.level.map.get(position);
+    tile.kind = TileKind::Door { open: true };
+    state.level.map.set(position, tile);
+    state.level.update_visibility();
+
+    spend_ticks(state, id, TICKS_MOVEMENT);
+}
+
+fn unlock_door<S: ScreenInterface>(
+    state: &mut MissionState,
+    id: CharacterId,
+    position: Point,
+    screen: &mut S,
+) {
+    let is_player = state.level.is_player(id);
+    let Some(actor) = state.level.find_character_mut(id) else {
+        return;
+    };
+    if let Some(key) = actor
+        .carried_items
+        .iter()
+        .position(|i| i.name == "Vault Key")
+    {
+        actor.carried_items.remove(key);
+        screen.push_floating_text("Unlocked the vault");
+        open_door(state, id, position);
+    } else if is_player {
+        screen.push_floating_text("The vault is locked");
+    }
+}
+
+fn pickup_any_items<S: ScreenInterface>(
+    state: &mut MissionState,
+    id: CharacterId,
+    dest: Point,
+    screen: &mut S,
+) {
     let items_at_new_position: Vec<Item> = state
         .level
         .items
@@ -65,11 +172,105 @@ fn pickup_any_items(state: &mut MissionState, id: CharacterId, dest: Point, scre
         .map(|(_, item)| item)
         .collect();
 
-    let actor = state.level.find_character_mut(id);
     for item in items_at_new_position {
         screen.push_floating_text(&format!("Picked up {}", item.name));
-        actor.carried_items.push(item);
+        if let Some(effect) = &item.on_pickup {
+            apply_effect(
+                &mut state.level,
+                &EffectSource::Item(item.name.clone()),
+                id,
+                effect,
+            );
+        } else if let Some(actor) = state.level.find_character_mut(id) {
+            actor.carried_items.push(item);
+        }
+    }
+}
+
+pub fn use_item<S: ScreenInterface>(
+    state: &mut MissionState,
+    source: CharacterId,
+    index: usize,
+    screen: &mut S,
+) {
+    let Some(actor) = state.level.find_character_mut(source) else {
+        return;
+    };
+    let Some(item) = actor.carried_items.get_mut(index) else {
+        return;
+    };
+    let Some(effect) = item.on_use.clone() else {
+        return;
+    };
+    let name = item.name.clone();
+    if let Some(charges) = &mut item.charges {
+        *charges = charges.saturating_sub(1);
+        if *charges == 0 {
+            actor.carried_items.remove(index);
+        }
+    }
+
+    let actor_name = actor.name.clone();
+    state
+        .level
+        .push_turn_log(format!("{actor_name} uses {name}"));
+    screen.play_sound(effect.sound());
+    apply_effect(&mut state.level, &EffectSource::Item(name), source, &effect);
+    spend_ticks(state, source, TICKS_TO_ACT);
+}
+
+pub fn equip_item<S: ScreenInterface>(
+    state: &mut MissionState,
+    source: CharacterId,
+    index: usize,
+    screen: &mut S,
+) {
+    let Some(actor) = state.level.find_character_mut(source) else {
+        return;
+    };
+    let Some(item) = actor.carried_items.get(index).filter(|i| i.equip.is_some()) else {
+        return;
+    };
+    screen.push_floating_text(&format!("Equipped {}", item.name));
+    actor.equip(index);
+    spend_ticks(state, source, TICKS_TO_ACT);
+}
+
+pub fn unequip_item<S: ScreenInterface>(
+    state: &mut MissionState,
+    source: CharacterId,
+    index: usize,
+    screen: &mut S,
+) {
+    let Some(actor) = state.level.find_character_mut(source) else {
+        return;
+    };
+    let Some(item) = actor.equipped.get(index) else {
+        return;
+    };
+    screen.push_floating_text(&format!("Took off {}", item.name));
+    actor.unequip(index);
+    spend_ticks(state, source, TICKS_TO_ACT);
+}
+
+// Left on the floor, walking back onto it picks it up again
+pub fn drop_item<S: ScreenInterface>(
+    state: &mut MissionState,
+    source: CharacterId,
+    index: usize,
+    screen: &mut S,
+) {
+    let Some(actor) = state.level.find_character_mut(source) else {
+        return;
+    };
+    if index >= actor.carried_items.len() {
+        return;
     }
+    let item = actor.carried_items.remove(index);
+    let position = actor.position;
+    screen.push_floating_text(&format!("Dropped {}", item.name));
+    state.level.items.push((position, item));
+    spend_ticks(state, source, TICKS_MOVEMENT);
 }
 
 pub fn weapon_attack<S: ScreenInterface>(
@@ -87,7 +288,7 @@ pub fn weapon_attack<S: ScreenInterface>(
         weapon.pierce,
     );
 
-    match gen_range(0, 3) {
+    match state.level.rng.gen_range(0, 3) {
         0 => screen.play_sound("impact_a"),
         1 => screen.play_sound("impact_b"),
         _ => screen.play_sound("attack_b"),
@@ -107,26 +308,24 @@ pub fn weapon_attack<S: ScreenInterface>(
 fn calculate_damage(
     level: &LevelState,
     source: &EffectSource,
-    target: CharacterId,
+    target: &Character,
     base_damage: i32,
     pierce: DamagePierce,
 ) -> (i32, String) {
     let mut damage_description = String::new();
     let source_name = source.name(level);
-    let target_name = level.find_character(target).name.clone();
+    let target_name = target.name.clone();
 
     // First check if we dodged due to Blind or Agile
     if source.has_status_effect(StatusEffectKind::Blind, level)
-        && gen_range(0.0, 1.0) < STATUS_EFFECT_CHANCE_BLIND_MISS
+        && level.rng.gen_chance(STATUS_EFFECT_CHANCE_BLIND_MISS)
     {
         return (
             0,
             format!("{source_name} missed their attack on {target_name}"),
         );
-    } else if level
-        .find_character(target)
-        .has_status_effect(StatusEffectKind::Agile)
-        && gen_range(0.0, 1.0) < STATUS_EFFECT_CHANCE_DODGE_MISS
+    } else if target.has_status_effect(StatusEffectKind::Agile)
+        && level.rng.gen_chance(STATUS_EFFECT_CHANCE_DODGE_MISS)
     {
         return (0, format!("{target_name} dodged {source_name}'s attack"));
     }
@@ -180,10 +379,9 @@ fn calculate_damage(
     (damage, damage_description)
 }
 
-fn get_target_defense(level: &LevelState, target: CharacterId) -> i32 {
-    let target_character = level.find_character(target);
-    let mut defense = target_character.defense;
-    if target_character.has_status_effect(StatusEffectKind::Protection) {
+fn get_target_defense(level: &LevelState, target: &Character) -> i32 {
+    let mut defense = target.defense;
+    if target.has_status_effect(StatusEffectKind::Protection) {
         defense += STATUS_EFFECT_PROTECTION_DEFENSE_BOOST;
     }
     defense += get_luck_defensive_rolls(level, target);
@@ -198,11 +396,10 @@ fn get_advantage_roll(level: &LevelState, source: &EffectSource) -> i32 {
     } else {
         vec![-1, 0, 1]
     };
-    *die.choose().unwrap()
+    *level.rng.choose(&die).unwrap()
 }
 
-fn get_luck_defensive_rolls(level: &LevelState, target: CharacterId) -> i32 {
-    let target = level.find_character(target);
+fn get_luck_defensive_rolls(level: &LevelState, target: &Character) -> i32 {
     let die = if target.has_status_effect(StatusEffectKind::Lucky) {
         vec![0, 1]
     } else if target.has_status_effect(StatusEffectKind::Cursed) {
@@ -210,7 +407,7 @@ fn get_luck_defensive_rolls(level: &LevelState, target: CharacterId) -> i32 {
     } else {
         vec![0]
     };
-    *die.choose().unwrap()
+    *level.rng.choose(&die).unwrap()
 }
 
 fn apply_damage(
@@ -220,22 +417,35 @@ fn apply_damage(
     damage: i32,
     pierce: DamagePierce,
 ) {
-    // HACK: There is a chance the player died due to a tick
-    if !level.does_character_exist(target) {
+    // They may have died already, say from a tick earlier in the same action
+    let Some(target_character) = level.find_character(target) else {
         return;
-    }
+    };
 
     let (final_damage, damage_description) =
-        calculate_damage(level, &source, target, damage, pierce);
+        calculate_damage(level, source, target_character, damage, pierce);
     level.push_turn_log(damage_description);
 
-    let target_character = level.find_character_mut(target);
+    let is_player = level.is_player(target);
+    let Some(target_character) = level.find_character_mut(target) else {
+        return;
+    };
 
     target_character.health.current -= final_damage;
 
     // We do not remove the player character, death checks will happen after action resolution
-    if target_character.health.is_dead() && !target_character.is_player() {
+    if target_character.health.is_dead() && !is_player {
+        let position = target_character.position;
+        let name = target_character.name.clone();
+        let loot = std::mem::take(&mut target_character.loot);
         level.remove_character(target);
+        if let Some(item) = loot
+            .roll(&level.rng)
+            .and_then(|i| Data::shared().find_item(i))
+        {
+            level.push_turn_log(format!("{name} dropped {}", item.name));
+            level.items.push((position, item));
+        }
     }
 
     if source.has_status_effect(StatusEffectKind::Lifesteal, level) {
@@ -246,7 +456,9 @@ fn apply_damage(
 }
 
 fn add_status(level: &mut LevelState, target: CharacterId, status: StatusEffect) {
-    let target_character = level.find_character_mut(target);
+    let Some(target_character) = level.find_character_mut(target) else {
+        return;
+    };
     let name = target_character.name.clone();
     let status_name = status.name.clone();
     target_character.status_effects.push(status);
@@ -255,22 +467,30 @@ fn add_status(level: &mut LevelState, target: CharacterId, status: StatusEffect)
 }
 
 fn apply_healing(level: &mut LevelState, target: &CharacterId, amount: i32) {
-    let target_character = level.find_character_mut(*target);
+    let Some(target_character) = level.find_character_mut(*target) else {
+        return;
+    };
     let name = target_character.name.clone();
     target_character.health.increase(amount);
 
     level.push_turn_log(format!("{name} is healed for {amount}"));
 }
 
-pub fn character_wait(state: &mut MissionState, id: CharacterId, screen: &mut Screen) {
+pub fn character_wait<S: ScreenInterface>(
+    state: &mut MissionState,
+    id: CharacterId,
+    screen: &mut S,
+) {
     if !is_hostile_nearby(state, id) {
         rest(state, id, screen);
     }
     spend_ticks(state, id, TICKS_TO_ACT);
 }
 
-fn rest(state: &mut MissionState, id: CharacterId, screen: &mut Screen) {
-    let actor = state.level.find_character_mut(id);
+fn rest<S: ScreenInterface>(state: &mut MissionState, id: CharacterId, screen: &mut S) {
+    let Some(actor) = state.level.find_character_mut(id) else {
+        return;
+    };
     let mut rested = false;
     if actor.health.percentage() < REST_HEALTH_PERCENTAGE {
         actor.health.current += 1;
@@ -286,60 +506,49 @@ fn rest(state: &mut MissionState, id: CharacterId, screen: &mut Screen) {
 }
 
 fn is_hostile_nearby(state: &MissionState, id: CharacterId) -> bool {
-    let actor = state.level.find_character(id);
-    let visibility = state.level.map.compute_visibility(actor.position);
-    if actor.is_player() {
-        state
-            .level
-            .characters
-            .iter()
-            .filter(|c| !c.is_player())
-            .any(|c| visibility.get(c.position))
-    } else {
-        visibility.get(state.get_player().position)
-    }
+    nearest_visible_hostile(&state.level, id).is_some()
 }
 
 pub fn is_hostile_near_player(level: &LevelState) -> bool {
-    let player = level.get_player();
-    let visibility = level.map.compute_visibility(player.position);
+    nearest_visible_hostile(level, level.player_id()).is_some()
+}
 
-    level
-        .characters
-        .iter()
-        .filter(|c| !c.is_player())
-        .any(|c| visibility.get(c.position))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillTarget {
+    Character(CharacterId),
+    // Area skills are aimed at a tile, which may be empty
+    Tile(Point),
 }
 
-pub fn apply_skill(
+pub fn apply_skill<S: ScreenInterface>(
     state: &mut MissionState,
     source: CharacterId,
-    target: CharacterId,
+    target: SkillTarget,
     skill_name: &str,
-    screen: &mut Screen,
+    screen: &mut S,
 ) {
-    if source == target {
-        state.level.push_turn_log(format!(
-            "{} uses {}",
-            state.level.find_character(source).name,
-            skill_name
-        ));
-    } else {
-        state.level.push_turn_log(format!(
-            "{} uses {} on {}",
-            state.level.find_character(source).name,
-            skill_name,
-            state.level.find_character(target).name,
-        ));
-    }
+    let Some(actor) = state.level.find_character(source) else {
+        return;
+    };
+    let log = match target {
+        SkillTarget::Character(target) if target != source => {
+            match state.level.find_character(target) {
+                Some(target) => format!("{} uses {} on {}", actor.name, skill_name, target.name),
+                None => format!("{} uses {}", actor.name, skill_name),
+            }
+        }
+        _ => format!("{} uses {}", actor.name, skill_name),
+    };
+    state.level.push_turn_log(log);
 
-    let actor = state.level.find_character_mut(source);
+    let Some(actor) = state.level.find_character_mut(source) else {
+        return;
+    };
+    let source_position = actor.position;
 
-    let skill = actor
-        .skills
-        .iter_mut()
-        .find(|s| s.name == skill_name)
-        .expect("Unable to find requested skill");
+    let Some(skill) = actor.skills.iter_mut().find(|s| s.name == skill_name) else {
+        return;
+    };
     match &mut skill.cost {
         SkillCost::None => {}
         SkillCost::Will(cost) => actor.will.current -= *cost,
@@ -347,19 +556,27 @@ pub fn apply_skill(
         SkillCost::Cooldown { ticks, cost } => *ticks = *cost,
     }
     let effect = skill.effect.clone();
+    let targeting = skill.targeting.clone();
 
-    match &skill.effect {
-        Effect::ApplyDamage { .. } => screen.play_sound("curse"),
-        Effect::AddStatus { .. } => screen.play_sound("swing"),
-        Effect::Heal { .. } => screen.play_sound("drip"),
-    }
+    screen.play_sound(skill.effect.sound());
 
-    apply_effect(
-        &mut state.level,
-        &EffectSource::Character(source),
-        target,
-        &effect,
-    );
+    let targets = match target {
+        SkillTarget::Character(target) => vec![target],
+        SkillTarget::Tile(position) => targeting
+            .affected_tiles(&state.level, source_position, position)
+            .into_iter()
+            .filter_map(|p| state.level.find_character_at_position(p))
+            .map(|c| c.id)
+            .collect(),
+    };
+    for target in targets {
+        apply_effect(
+            &mut state.level,
+            &EffectSource::Character(source),
+            target,
+            &effect,
+        );
+    }
 
     spend_ticks(state, source, TICKS_TO_ACT);
 }
@@ -368,24 +585,26 @@ pub fn apply_skill(
 pub enum EffectSource {
     Character(CharacterId),
     StatusEffect(String),
+    Item(String),
 }
 
 impl EffectSource {
     pub fn has_status_effect(&self, kind: StatusEffectKind, level: &LevelState) -> bool {
         match self {
-            EffectSource::Character(character_id) => {
-                level.find_character(*character_id).has_status_effect(kind)
-            }
-            EffectSource::StatusEffect(_) => false,
+            EffectSource::Character(character_id) => level
+                .find_character(*character_id)
+                .is_some_and(|c| c.has_status_effect(kind)),
+            EffectSource::StatusEffect(_) | EffectSource::Item(_) => false,
         }
     }
 
     pub fn name(&self, level: &LevelState) -> String {
         match self {
-            EffectSource::Character(character_id) => {
-                level.find_character(*character_id).name.clone()
-            }
-            EffectSource::StatusEffect(name) => name.clone(),
+            // Whatever it was is gone by the time the effect lands
+            EffectSource::Character(character_id) => level
+                .find_character(*character_id)
+                .map_or_else(|| "Something".to_string(), |c| c.name.clone()),
+            EffectSource::StatusEffect(name) | EffectSource::Item(name) => name.clone(),
         }
     }
 }
@@ -396,11 +615,6 @@ pub fn apply_effect(
     target: CharacterId,
     effect: &Effect,
 ) {
-    // HACK - See if target was killed before we got here
-    if !level.does_character_exist(target) {
-        return;
-    }
-
     match effect {
         Effect::ApplyDamage {
             damage,
@@ -424,20 +638,99 @@ pub fn apply_effect(
         Effect::AddStatus { effect } => {
             add_status(level, target, effect.clone());
         }
+        Effect::Sequence(effects) => {
+            for effect in effects {
+                apply_effect(level, source, target, effect);
+            }
+        }
+        Effect::IfTargetHasStatus {
+            kind,
+            effect,
+            otherwise,
+        } => {
+            match level
+                .find_character(target)
+                .map(|c| c.has_status_effect(*kind))
+            {
+                Some(true) => apply_effect(level, source, target, effect),
+                Some(false) => {
+                    if let Some(otherwise) = otherwise {
+                        apply_effect(level, source, target, otherwise);
+                    }
+                }
+                None => {}
+            }
+        }
+        Effect::WithChance { percent, effect } => {
+            if level.rng.gen_chance(*percent as f64 / 100.0) {
+                apply_effect(level, source, target, effect);
+            }
+        }
+        Effect::Summon { character } => {
+            summon(level, source, target, character);
+        }
     }
 }
 
-pub fn ascend_stars(state: &mut MissionState, screen: &mut Screen) {
-    let player = state.get_player();
-    let on_exit = state.level.map.get(player.position).kind == TileKind::Exit;
+fn summon(level: &mut LevelState, source: &EffectSource, target: CharacterId, name: &str) {
+    let Some(target_character) = level.find_character(target) else {
+        return;
+    };
+    let faction = match source {
+        EffectSource::Character(id) => level
+            .find_character(*id)
+            .map_or(target_character.faction, |c| c.faction),
+        EffectSource::StatusEffect(_) | EffectSource::Item(_) => target_character.faction,
+    };
+    let Some(position) = adjacent_squares(
+        level,
+        target_character.position,
+        PathCharacterOptions::AllCharactersBlock,
+    )
+    .first()
+    .copied() else {
+        level.push_turn_log(format!("There is no room for the {name}"));
+        return;
+    };
+
+    let mut summoned = Data::shared().get_character(name);
+    summoned.position = position;
+    summoned.faction = faction;
+    summoned.summon_ticks = Some(SUMMON_DURATION_TICKS);
+    // Nothing to farm from calling up monsters
+    summoned.loot = LootTable::default();
+
+    level.push_turn_log(format!("A {} appears", summoned.name));
+    level.add_character(summoned);
+}
+
+pub fn take_stairs<S: ScreenInterface>(state: &mut MissionState, screen: &mut S) {
+    let Some(player) = state.level.get_player() else {
+        return;
+    };
+    let player_id = player.id;
     let has_runestone = player.carried_items.iter().any(|i| i.name == "Runestone");
-    if on_exit {
-        if has_runestone {
-            spend_ticks(state, player.id, TICKS_TO_ACT);
-            state.mission_complete = true;
-        } else {
-            screen.push_floating_text("Retrieve the Runestone first!");
+
+    match state.level.map.get(player.position).kind {
+        TileKind::Exit if state.depth > 0 => {
+            state.change_floor(state.depth - 1);
+            screen.push_floating_text(&format!("Climbed to floor {}", state.depth + 1));
+            spend_ticks(state, player_id, TICKS_TO_ACT);
         }
+        TileKind::Exit => {
+            if has_runestone {
+                spend_ticks(state, player_id, TICKS_TO_ACT);
+                state.mission_complete = true;
+            } else {
+                screen.push_floating_text("Retrieve the Runestone first!");
+            }
+        }
+        TileKind::StairsDown => {
+            state.change_floor(state.depth + 1);
+            screen.push_floating_text(&format!("Descended to floor {}", state.depth + 1));
+            spend_ticks(state, player_id, TICKS_TO_ACT);
+        }
+        _ => {}
     }
 }
 
@@ -445,13 +738,14 @@ pub fn ascend_stars(state: &mut MissionState, screen: &mut Screen) {
 mod tests {
     use crate::campaign::{CampaignState, RuneKinds};
     use crate::mission::*;
+    use crate::prelude::{GameRng, Point};
     use crate::screen::EmptyScreen;
 
     #[test]
     fn on_hit() {
         let (id, mut level) = create_test_map();
 
-        level.find_character_mut(id).weapon.on_hit = Some(Effect::AddStatus {
+        level.find_character_mut(id).unwrap().weapon.on_hit = Some(Effect::AddStatus {
             effect: StatusEffect {
                 name: "Weakness".to_string(),
                 kind: StatusEffectKind::Weakness,
@@ -460,7 +754,7 @@ mod tests {
             },
         });
 
-        let character = level.get_player().clone();
+        let character = level.get_player().unwrap().clone();
         let mut mission_state = MissionState {
             level,
             frame: 0,
@@ -468,10 +762,19 @@ mod tests {
             mission_complete: false,
             campaign: CampaignState::new(character),
             active_rune: RuneKinds::Fire,
+            replay: None,
+            controller: PlayerController::Keyboard,
+            floors: vec![None],
+            depth: 0,
         };
 
-        let player_id = mission_state.level.get_player().id;
-        let weapon = mission_state.level.find_character(id).weapon.clone();
+        let player_id = mission_state.level.get_player().unwrap().id;
+        let weapon = mission_state
+            .level
+            .find_character(id)
+            .unwrap()
+            .weapon
+            .clone();
 
         weapon_attack(
             &mut mission_state,
@@ -485,7 +788,352 @@ mod tests {
             mission_state
                 .level
                 .get_player()
+                .unwrap()
+                .has_status_effect(StatusEffectKind::Weakness)
+        );
+    }
+
+    #[test]
+    fn same_seed_same_combat() {
+        let run = || {
+            let (id, mut level) = create_test_map();
+            level.rng = GameRng::new(1234);
+
+            let character = level.get_player().unwrap().clone();
+            let mut mission_state = MissionState {
+                level,
+                frame: 0,
+                current_actor: CurrentActor::PlayerStandardAction,
+                mission_complete: false,
+                campaign: CampaignState::new(character),
+                active_rune: RuneKinds::Fire,
+                replay: None,
+                controller: PlayerController::Keyboard,
+                floors: vec![None],
+                depth: 0,
+            };
+
+            let player_id = mission_state.level.get_player().unwrap().id;
+            let weapon = mission_state
+                .level
+                .find_character(id)
+                .unwrap()
+                .weapon
+                .clone();
+            for _ in 0..10 {
+                weapon_attack(
+                    &mut mission_state,
+                    id,
+                    player_id,
+                    weapon.clone(),
+                    &mut EmptyScreen {},
+                );
+            }
+            (
+                mission_state.level.get_player().unwrap().health.current,
+                mission_state.level.turn_log.clone(),
+            )
+        };
+
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn area_skill_hits_everyone_in_area() {
+        let (id, mut level) = create_test_map();
+        level.find_character_mut(id).unwrap().position = Point::new(1, 3);
+        let mut second = Data::load().unwrap().get_character("Bat");
+        second.position = Point::new(1, 2);
+        let second_id = second.id;
+        level.characters.push(second);
+
+        level.get_player_mut().unwrap().skills = vec![Skill {
+            name: "Nova".to_string(),
+            cost: SkillCost::None,
+            effect: Effect::ApplyDamage {
+                damage: 100,
+                on_hit: None,
+                on_hit_self: None,
+                pierce: DamagePierce::Full,
+            },
+            targeting: SkillTargeting::PointBlank { radius: 2 },
+        }];
+
+        let character = level.get_player().unwrap().clone();
+        let player_id = character.id;
+        let player_position = character.position;
+        let mut mission_state = MissionState {
+            level,
+            frame: 0,
+            current_actor: CurrentActor::PlayerStandardAction,
+            mission_complete: false,
+            campaign: CampaignState::new(character),
+            active_rune: RuneKinds::Fire,
+            replay: None,
+            controller: PlayerController::Keyboard,
+            floors: vec![None],
+            depth: 0,
+        };
+
+        apply_skill(
+            &mut mission_state,
+            player_id,
+            SkillTarget::Tile(player_position),
+            "Nova",
+            &mut EmptyScreen {},
+        );
+        assert!(mission_state.level.find_character(id).is_none());
+        assert!(mission_state.level.find_character(second_id).is_none());
+        assert!(!mission_state.is_player_dead());
+    }
+
+    fn weakness() -> Effect {
+        Effect::AddStatus {
+            effect: StatusEffect {
+                name: "Weakness".to_string(),
+                kind: StatusEffectKind::Weakness,
+                duration: Some(200),
+                on_complete: None,
+            },
+        }
+    }
+
+    #[test]
+    fn sequence_applies_every_effect() {
+        let (id, mut level) = create_test_map();
+        let starting_health = level.find_character(id).unwrap().health.current;
+
+        let effect = Effect::Sequence(vec![
+            Effect::ApplyDamage {
+                damage: 1,
+                on_hit: None,
+                on_hit_self: None,
+                pierce: DamagePierce::Full,
+            },
+            weakness(),
+        ]);
+        let player = EffectSource::Character(level.get_player().unwrap().id);
+        apply_effect(&mut level, &player, id, &effect);
+
+        let bat = level.find_character(id).unwrap();
+        assert!(bat.health.current < starting_health);
+        assert!(bat.has_status_effect(StatusEffectKind::Weakness));
+    }
+
+    #[test]
+    fn conditional_effects() {
+        let (id, mut level) = create_test_map();
+        let player = EffectSource::Character(level.get_player().unwrap().id);
+
+        let never = Effect::WithChance {
+            percent: 0,
+            effect: Box::new(weakness()),
+        };
+        apply_effect(&mut level, &player, id, &never);
+        assert!(
+            !level
+                .find_character(id)
+                .unwrap()
                 .has_status_effect(StatusEffectKind::Weakness)
         );
+
+        let rooted = Effect::AddStatus {
+            effect: StatusEffect {
+                name: "Rooted".to_string(),
+                kind: StatusEffectKind::Rooted,
+                duration: Some(200),
+                on_complete: None,
+            },
+        };
+        let conditional = Effect::IfTargetHasStatus {
+            kind: StatusEffectKind::Weakness,
+            effect: Box::new(rooted),
+            otherwise: Some(Box::new(Effect::WithChance {
+                percent: 100,
+                effect: Box::new(weakness()),
+            })),
+        };
+        apply_effect(&mut level, &player, id, &conditional);
+        let bat = level.find_character(id).unwrap();
+        assert!(bat.has_status_effect(StatusEffectKind::Weakness));
+        assert!(!bat.has_status_effect(StatusEffectKind::Rooted));
+
+        apply_effect(&mut level, &player, id, &conditional);
+        assert!(
+            level
+                .find_character(id)
+                .unwrap()
+                .has_status_effect(StatusEffectKind::Rooted)
+        );
+    }
+
+    #[test]
+    fn inventory_items() {
+        let (_, mut level) = create_test_map();
+        let data = Data::load().unwrap();
+        let player = level.get_player_mut().unwrap();
+        player.health.current = 1;
+        player.carried_items = vec![
+            data.get_item("Health Potion"),
+            data.get_item("Iron Sword"),
+            data.get_item("Ring of Striking"),
+        ];
+        let player_id = player.id;
+        let damage = player.weapon.damage;
+
+        let character = level.get_player().unwrap().clone();
+        let mut mission_state = MissionState {
+            level,
+            frame: 0,
+            current_actor: CurrentActor::PlayerStandardAction,
+            mission_complete: false,
+            campaign: CampaignState::new(character),
+            active_rune: RuneKinds::Fire,
+            replay: None,
+            controller: PlayerController::Keyboard,
+            floors: vec![None],
+            depth: 0,
+        };
+        let screen = &mut EmptyScreen {};
+
+        use_item(&mut mission_state, player_id, 0, screen);
+        let player = mission_state.level.get_player().unwrap();
+        assert!(player.health.current > 1);
+        assert_eq!(player.carried_items[0].charges, Some(6));
+
+        equip_item(&mut mission_state, player_id, 1, screen);
+        equip_item(&mut mission_state, player_id, 1, screen);
+        let player = mission_state.level.get_player().unwrap();
+        assert_eq!(player.weapon.damage, damage + 3);
+        assert_eq!(player.equipped.len(), 2);
+
+        // Taking something off takes its bonus with it
+        unequip_item(&mut mission_state, player_id, 0, screen);
+        assert_eq!(
+            mission_state.level.get_player().unwrap().weapon.damage,
+            damage + 1
+        );
+
+        let position = mission_state.level.get_player().unwrap().position;
+        drop_item(&mut mission_state, player_id, 0, screen);
+        assert_eq!(
+            mission_state
+                .level
+                .get_player()
+                .unwrap()
+                .carried_items
+                .len(),
+            1
+        );
+        assert!(
+            mission_state
+                .level
+                .items
+                .iter()
+                .any(|(p, i)| *p == position && i.name == "Health Potion")
+        );
+    }
+
+    #[test]
+    fn monsters_drop_loot_where_they_die() {
+        let (id, mut level) = create_test_map();
+        let monster = level.find_character_mut(id).unwrap();
+        monster.loot = LootTable {
+            drop_chance: 1.0,
+            items: vec![LootEntry {
+                item: "Chain Mail".to_string(),
+                weight: 1,
+            }],
+        };
+        let position = monster.position;
+        let player_id = level.get_player().unwrap().id;
+
+        super::apply_damage(
+            &mut level,
+            &EffectSource::Character(player_id),
+            id,
+            1000,
+            DamagePierce::Full,
+        );
+
+        assert!(level.find_character(id).is_none());
+        assert!(
+            level
+                .items
+                .iter()
+                .any(|(p, i)| *p == position && i.name == "Chain Mail")
+        );
+    }
+
+    #[test]
+    fn missing_characters_are_passed_over() {
+        let (id, mut level) = create_test_map();
+        level.remove_character(id);
+
+        let effect = Effect::Sequence(vec![
+            Effect::ApplyDamage {
+                damage: 1,
+                on_hit: Some(Box::new(weakness())),
+                on_hit_self: None,
+                pierce: DamagePierce::Full,
+            },
+            Effect::Heal { amount: 5 },
+            Effect::Summon {
+                character: "Wolf".to_string(),
+            },
+        ]);
+        apply_effect(&mut level, &EffectSource::Character(id), id, &effect);
+        assert_eq!(level.characters.len(), 1);
+
+        // Their turn is waited away, handing it on to someone still around
+        let action = default_ai_action(&mut level, id);
+        assert_eq!(
+            action,
+            HandleInputResponse::Action(Some(RequestedAction::Wait(id)))
+        );
+
+        let character = level.get_player().unwrap().clone();
+        let mut mission_state = MissionState {
+            level,
+            frame: 0,
+            current_actor: CurrentActor::EnemyAction(id),
+            mission_complete: false,
+            campaign: CampaignState::new(character),
+            active_rune: RuneKinds::Fire,
+            replay: None,
+            controller: PlayerController::Keyboard,
+            floors: vec![None],
+            depth: 0,
+        };
+        mission_state.process_action(RequestedAction::Wait(id), &mut EmptyScreen {});
+        assert_eq!(
+            mission_state.current_actor,
+            CurrentActor::PlayerStandardAction
+        );
+    }
+
+    #[test]
+    fn summons_take_unused_ids() {
+        let (id, mut level) = create_test_map();
+        let before = level.characters.len();
+        let summon = Effect::Summon {
+            character: "Wolf".to_string(),
+        };
+        // One each, as the corridor only has room beside either end
+        let player_id = level.player_id();
+        apply_effect(&mut level, &EffectSource::Character(id), id, &summon);
+        apply_effect(
+            &mut level,
+            &EffectSource::Character(player_id),
+            player_id,
+            &summon,
+        );
+        assert_eq!(level.characters.len(), before + 2);
+
+        let mut ids = level.characters.iter().map(|c| c.id).collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), before + 2);
+        assert_eq!(level.get_player().unwrap().faction, Faction::Player);
     }
 }
@@ -9,54 +9,64 @@ use crate::prelude::*;
 pub struct LevelState {
     pub map: Map,
     pub characters: Vec<Character>,
+    // Kept when the player leaves for another floor, so it is theirs again on return
+    player: CharacterId,
     pub items: Vec<(Point, Item)>,
     visibility: VisibilityMap,
     pub turn_log: Vec<String>,
+    #[serde(default)]
+    pub rng: GameRng,
+    // Handed out by add_character, so ids are the same on every load and replay
+    #[serde(default)]
+    next_character_id: CharacterId,
 }
 
 impl LevelState {
-    pub fn new(map: Map, characters: Vec<Character>, items: Vec<(Point, Item)>) -> Self {
+    pub fn new(
+        map: Map,
+        player: CharacterId,
+        characters: Vec<Character>,
+        items: Vec<(Point, Item)>,
+    ) -> Self {
         let mut this = Self {
             map,
             characters,
+            player,
             items,
             visibility: VisibilityMap::new(),
             turn_log: vec![],
+            rng: GameRng::default(),
+            next_character_id: CharacterId::default(),
         };
+        this.skip_used_character_ids();
         this.update_visibility();
         this
     }
 
-    pub fn get_player(&self) -> &Character {
-        self.characters
-            .iter()
-            .find(|c| c.is_player())
-            .expect("Player must still exist")
+    pub fn player_id(&self) -> CharacterId {
+        self.player
     }
 
-    pub fn get_player_mut(&mut self) -> &mut Character {
-        self.characters
-            .iter_mut()
-            .find(|c| c.is_player())
-            .expect("Player must still exist")
+    pub fn is_player(&self, id: CharacterId) -> bool {
+        self.player == id
     }
 
-    pub fn does_character_exist(&self, id: CharacterId) -> bool {
-        self.characters.iter().any(|c| c.id == id)
+    // None while the player is on another floor
+    pub fn get_player(&self) -> Option<&Character> {
+        self.find_character(self.player)
     }
 
-    pub fn find_character(&self, id: CharacterId) -> &Character {
-        self.characters
-            .iter()
-            .find(|c| c.id == id)
-            .expect("Action actor exists")
+    pub fn get_player_mut(&mut self) -> Option<&mut Character> {
+        self.find_character_mut(self.player)
     }
 
-    pub fn find_character_mut(&mut self, id: CharacterId) -> &mut Character {
-        self.characters
-            .iter_mut()
-            .find(|c| c.id == id)
-            .expect("Action actor exists")
+    // None once they have died or faded away
+    pub fn find_character(&self, id: CharacterId) -> Option<&Character> {
+        self.characters.iter().find(|c| c.id == id)
+    }
+
+    pub fn find_character_mut(&mut self, id: CharacterId) -> Option<&mut Character> {
+        self.characters.iter_mut().find(|c| c.id == id)
     }
 
     pub fn player_can_see(&self, point: Point) -> bool {
@@ -71,6 +81,29 @@ impl LevelState {
         self.map.in_bounds(point) && self.map.get(point).can_enter()
     }
 
+    // Never hand out an id already on the level, which also covers saves from before the counter
+    fn skip_used_character_ids(&mut self) {
+        if let Some(highest) = self.characters.iter().map(|c| c.id).max()
+            && highest >= self.next_character_id
+        {
+            self.next_character_id = highest.after();
+        }
+    }
+
+    fn new_character_id(&mut self) -> CharacterId {
+        self.skip_used_character_ids();
+        let id = self.next_character_id;
+        self.next_character_id = id.after();
+        id
+    }
+
+    pub fn add_character(&mut self, mut character: Character) -> CharacterId {
+        let id = self.new_character_id();
+        character.id = id;
+        self.characters.push(character);
+        id
+    }
+
     pub fn remove_character(&mut self, id: CharacterId) {
         self.characters.retain(|c| c.id != id);
     }
@@ -88,7 +121,7 @@ impl LevelState {
             if screen.camera.is_in_view(character.position)
                 && self.visibility.get(character.position)
             {
-                character.render(screen);
+                character.render(screen, !self.is_player(character.id));
             }
         }
 
@@ -97,7 +130,9 @@ impl LevelState {
     }
 
     fn render_hud(&mut self, screen: &Screen) {
-        let player = self.get_player();
+        let Some(player) = self.get_player() else {
+            return;
+        };
 
         let health = player.health.clone();
         let health_percentage = health.percentage();
@@ -133,9 +168,9 @@ impl LevelState {
 
         Screen::draw_centered_text(&format!("{}/{}", will.current, will.max), 17, 31.0, None);
 
-        let mut offset = self.draw_skills();
-        self.draw_items(&mut offset);
-        self.draw_status_effects(&mut offset);
+        let mut offset = Self::draw_skills(player);
+        Self::draw_items(player, &mut offset);
+        Self::draw_status_effects(player, &mut offset);
 
         self.draw_tooltips(screen);
         self.draw_log();
@@ -222,37 +257,25 @@ impl LevelState {
             );
         }
         if self.map.in_bounds(position) {
-            match self.map.get(position).kind {
-                TileKind::Exit => {
-                    let y = if mouse_position.1 < 100.0 {
-                        mouse_position.1 + 55.0
-                    } else {
-                        mouse_position.1 - 15.0
-                    };
-                    draw_rectangle(mouse_position.0 - 20.0, y - 30.0, 200.0, 40.0, BLACK);
-                    draw_rectangle_lines(
-                        mouse_position.0 - 20.0,
-                        y - 30.0,
-                        200.0,
-                        40.0,
-                        3.0,
-                        WHITE,
-                    );
-                    draw_text(
-                        &format!("Stairs Up"),
-                        mouse_position.0,
-                        y - 5.0,
-                        20.0,
-                        WHITE,
-                    );
-                }
-                _ => {}
+            let stairs = match self.map.get(position).kind {
+                TileKind::Exit => Some("Stairs Up"),
+                TileKind::StairsDown => Some("Stairs Down"),
+                _ => None,
+            };
+            if let Some(stairs) = stairs {
+                let y = if mouse_position.1 < 100.0 {
+                    mouse_position.1 + 55.0
+                } else {
+                    mouse_position.1 - 15.0
+                };
+                draw_rectangle(mouse_position.0 - 20.0, y - 30.0, 200.0, 40.0, BLACK);
+                draw_rectangle_lines(mouse_position.0 - 20.0, y - 30.0, 200.0, 40.0, 3.0, WHITE);
+                draw_text(stairs, mouse_position.0, y - 5.0, 20.0, WHITE);
             }
         }
     }
 
-    fn draw_skills(&self) -> f32 {
-        let player = self.get_player();
+    fn draw_skills(player: &Character) -> f32 {
         let mut offset = 60.0;
 
         for (i, skill) in player.skills.iter().enumerate() {
@@ -286,9 +309,7 @@ impl LevelState {
         offset + 35.0
     }
 
-    fn draw_status_effects(&self, offset: &mut f32) {
-        let player = self.get_player();
-
+    fn draw_status_effects(player: &Character, offset: &mut f32) {
         if !player.status_effects.is_empty() {
             draw_text("Status:", screen_width() - 250.0, *offset, 22.0, WHITE);
             *offset += 22.0;
@@ -301,16 +322,26 @@ impl LevelState {
         *offset += 18.0;
     }
 
-    fn draw_items(&self, offset: &mut f32) {
-        let player = self.get_player();
-
-        if !player.carried_items.is_empty() {
-            draw_text("Inventory:", screen_width() - 250.0, *offset, 22.0, WHITE);
+    fn draw_items(player: &Character, offset: &mut f32) {
+        if !player.carried_items.is_empty() || !player.equipped.is_empty() {
+            draw_text(
+                "Inventory (i):",
+                screen_width() - 250.0,
+                *offset,
+                22.0,
+                WHITE,
+            );
             *offset += 22.0;
         }
 
-        for item in &player.carried_items {
-            draw_text(&item.name, screen_width() - 230.0, *offset, 22.0, WHITE);
+        for item in player.equipped.iter().chain(&player.carried_items) {
+            draw_text(
+                &item.describe(),
+                screen_width() - 230.0,
+                *offset,
+                22.0,
+                WHITE,
+            );
             *offset += 18.0;
         }
         *offset += 18.0;
@@ -325,7 +356,10 @@ impl LevelState {
     }
 
     pub fn update_visibility(&mut self) {
-        self.visibility = self.map.compute_visibility(self.get_player().position);
+        let Some(player) = self.get_player() else {
+            return;
+        };
+        self.visibility = self.map.compute_visibility(player.position);
         for x in 0..SCREEN_WIDTH {
             for y in 0..SCREEN_HEIGHT {
                 let position = Point::new(x, y);
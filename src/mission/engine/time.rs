@@ -2,19 +2,20 @@ use crate::mission::*;
 use crate::prelude::*;
 
 pub fn spend_ticks(state: &mut MissionState, id: CharacterId, amount: i32) {
-    state.level.find_character_mut(id).ticks -= amount;
+    // Whoever acted may not have lived through it
+    if let Some(actor) = state.level.find_character_mut(id) {
+        actor.ticks -= amount;
+    }
 
     if let Some(next) = find_next_actor(&mut state.level) {
         // There is a chance the next actor died when time moved forward
-        if state.level.does_character_exist(next) {
-            if state.level.find_character(next).is_player() {
-                state.current_actor = CurrentActor::PlayerStandardAction;
-            } else {
-                state.current_actor = CurrentActor::EnemyAction(next);
-            }
-        } else {
+        if state.level.find_character(next).is_none() {
             // Spend zero ticks to pick the next valid entry
             spend_ticks(state, id, 0);
+        } else if state.level.is_player(next) {
+            state.current_actor = CurrentActor::PlayerStandardAction;
+        } else {
+            state.current_actor = CurrentActor::EnemyAction(next);
         }
     }
 }
@@ -36,12 +37,20 @@ fn find_next_actor(level: &mut LevelState) -> Option<CharacterId> {
 
 fn add_ticks(level: &mut LevelState, amount: i32) {
     let mut effects_to_apply = vec![];
+    let mut faded = vec![];
 
     for character in &mut level.characters {
         if !character.has_status_effect(StatusEffectKind::Stun) {
             character.ticks += amount;
         }
 
+        if let Some(summon_ticks) = &mut character.summon_ticks {
+            *summon_ticks -= amount;
+            if *summon_ticks <= 0 {
+                faded.push(character.id);
+            }
+        }
+
         for status in &mut character.status_effects {
             status.tick(amount);
         }
@@ -88,19 +97,28 @@ fn add_ticks(level: &mut LevelState, amount: i32) {
             &effect,
         );
     }
+
+    for id in faded {
+        if let Some(character) = level.find_character(id) {
+            let name = character.name.clone();
+            level.remove_character(id);
+            level.push_turn_log(format!("The {name} fades away"));
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::mission::engine::time::add_ticks;
     use crate::mission::*;
+    use crate::prelude::SUMMON_DURATION_TICKS;
     use crate::util::Point;
 
     #[test]
     fn reapply_status() {
         let (id, mut level) = create_test_map();
 
-        let bat = level.find_character_mut(id);
+        let bat = level.find_character_mut(id).unwrap();
         bat.status_effects.push(StatusEffect {
             name: "Burn".to_string(),
             kind: StatusEffectKind::RepeatingNegative,
@@ -120,17 +138,18 @@ mod tests {
 
         for _ in 0..3 {
             add_ticks(&mut level, 100);
-            let bat = level.find_character(id);
+            let bat = level.find_character(id).unwrap();
             assert!(bat.has_status_effect(StatusEffectKind::RepeatingNegative));
             assert!(bat.health.current < last_health);
             last_health = bat.health.current;
         }
 
         add_ticks(&mut level, 100);
-        let bat = level.find_character(id);
+        let bat = level.find_character(id).unwrap();
         assert!(
             !level
                 .find_character(id)
+                .unwrap()
                 .has_status_effect(StatusEffectKind::RepeatingNegative)
         );
         assert!(bat.health.current < last_health);
@@ -140,7 +159,7 @@ mod tests {
     fn immortal_status() {
         let (id, mut level) = create_test_map();
 
-        let bat = level.find_character_mut(id);
+        let bat = level.find_character_mut(id).unwrap();
         bat.status_effects.push(StatusEffect {
             name: "Lifesteal".to_string(),
             kind: StatusEffectKind::Lifesteal,
@@ -150,7 +169,7 @@ mod tests {
 
         for _ in 0..100 {
             add_ticks(&mut level, 100);
-            let bat = level.find_character(id);
+            let bat = level.find_character(id).unwrap();
             assert!(bat.has_status_effect(StatusEffectKind::Lifesteal));
         }
     }
@@ -159,7 +178,7 @@ mod tests {
     fn stun_prevents_tick_gain_for_period() {
         let (id, mut level) = create_test_map();
 
-        let bat = level.find_character_mut(id);
+        let bat = level.find_character_mut(id).unwrap();
         bat.status_effects.push(StatusEffect {
             name: "Stunned".to_string(),
             kind: StatusEffectKind::Stun,
@@ -168,28 +187,30 @@ mod tests {
         });
 
         add_ticks(&mut level, 100);
-        assert_eq!(level.find_character(id).ticks, 0);
+        assert_eq!(level.find_character(id).unwrap().ticks, 0);
         assert!(
             level
                 .find_character(id)
+                .unwrap()
                 .has_status_effect(StatusEffectKind::Stun)
         );
         add_ticks(&mut level, 100);
-        assert_eq!(level.find_character(id).ticks, 0);
+        assert_eq!(level.find_character(id).unwrap().ticks, 0);
         assert!(
             !level
                 .find_character(id)
+                .unwrap()
                 .has_status_effect(StatusEffectKind::Stun)
         );
         add_ticks(&mut level, 100);
-        assert_eq!(level.find_character(id).ticks, 100);
+        assert_eq!(level.find_character(id).unwrap().ticks, 100);
     }
 
     #[test]
     fn skills_recharge() {
         let (id, mut level) = create_test_map();
 
-        let bat = level.find_character_mut(id);
+        let bat = level.find_character_mut(id).unwrap();
         bat.skills.push(Skill {
             name: "Bite".to_string(),
             cost: SkillCost::Cooldown {
@@ -210,7 +231,7 @@ mod tests {
 
         add_ticks(&mut level, 100);
         assert_eq!(
-            level.find_character(id).skills[0].cost,
+            level.find_character(id).unwrap().skills[0].cost,
             SkillCost::Cooldown {
                 ticks: 100,
                 cost: 200
@@ -219,11 +240,35 @@ mod tests {
 
         add_ticks(&mut level, 150);
         assert_eq!(
-            level.find_character(id).skills[0].cost,
+            level.find_character(id).unwrap().skills[0].cost,
             SkillCost::Cooldown {
                 ticks: 0,
                 cost: 200
             }
         );
     }
+
+    #[test]
+    fn summons_fight_for_caster_then_fade() {
+        let (_, mut level) = create_test_map();
+        let player = level.get_player().unwrap().id;
+
+        apply_effect(
+            &mut level,
+            &EffectSource::Character(player),
+            player,
+            &Effect::Summon {
+                character: "Wolf".to_string(),
+            },
+        );
+        // The only open tile next to the player
+        let wolf = level.find_character_at_position(Point::new(1, 2)).unwrap();
+        assert_eq!(wolf.faction, Faction::Player);
+        let wolf = wolf.id;
+
+        add_ticks(&mut level, SUMMON_DURATION_TICKS - 100);
+        assert!(level.find_character(wolf).is_some());
+        add_ticks(&mut level, 100);
+        assert!(level.find_character(wolf).is_none());
+    }
 }
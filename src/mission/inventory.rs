@@ -0,0 +1,131 @@
+use macroquad::shapes::{draw_rectangle, draw_rectangle_lines};
+use macroquad::text::draw_text;
+use macroquad::window::screen_width;
+
+use crate::mission::*;
+use crate::prelude::*;
+
+// Browsing what the player wears and carries, listed in that order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryInfo {
+    pub selection: usize,
+}
+
+enum InventoryEntry<'a> {
+    Equipped(usize, &'a Item),
+    Carried(usize, &'a Item),
+}
+
+fn entries(player: &Character) -> Vec<InventoryEntry<'_>> {
+    player
+        .equipped
+        .iter()
+        .enumerate()
+        .map(|(i, item)| InventoryEntry::Equipped(i, item))
+        .chain(
+            player
+                .carried_items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| InventoryEntry::Carried(i, item)),
+        )
+        .collect()
+}
+
+impl InventoryInfo {
+    pub fn new() -> Self {
+        Self { selection: 0 }
+    }
+
+    pub fn handle_input(&mut self, player: &Character) -> HandleInputResponse {
+        let entries = entries(player);
+        let source = player.id;
+
+        let action = if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::I) {
+            return HandleInputResponse::ChangeActor(CurrentActor::PlayerStandardAction);
+        } else if let Some(delta) = handle_movement_key() {
+            if delta.y > 0 && self.selection + 1 < entries.len() {
+                self.selection += 1;
+            } else if delta.y < 0 && self.selection > 0 {
+                self.selection -= 1;
+            }
+            None
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            match entries.get(self.selection) {
+                Some(InventoryEntry::Carried(index, item)) if item.on_use.is_some() => {
+                    Some(RequestedAction::UseItem {
+                        source,
+                        index: *index,
+                    })
+                }
+                _ => None,
+            }
+        } else if is_key_pressed(KeyCode::E) {
+            match entries.get(self.selection) {
+                Some(InventoryEntry::Equipped(index, _)) => Some(RequestedAction::UnequipItem {
+                    source,
+                    index: *index,
+                }),
+                Some(InventoryEntry::Carried(index, item)) if item.equip.is_some() => {
+                    Some(RequestedAction::EquipItem {
+                        source,
+                        index: *index,
+                    })
+                }
+                _ => None,
+            }
+        } else if is_key_pressed(KeyCode::D) {
+            match entries.get(self.selection) {
+                Some(InventoryEntry::Carried(index, _)) => Some(RequestedAction::DropItem {
+                    source,
+                    index: *index,
+                }),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        HandleInputResponse::Action(action)
+    }
+
+    pub fn render(&self, level: &LevelState) {
+        let Some(player) = level.get_player() else {
+            return;
+        };
+        let entries = entries(player);
+        let (left, top, width) = ((screen_width() - 500.0) / 2.0, 120.0, 500.0);
+        let height = 120.0 + 24.0 * entries.len().max(1) as f32;
+
+        draw_rectangle(left, top, width, height, BLACK);
+        draw_rectangle_lines(left, top, width, height, 3.0, WHITE);
+        draw_text("Inventory", left + 20.0, top + 35.0, 26.0, WHITE);
+
+        let mut offset = top + 70.0;
+        if entries.is_empty() {
+            draw_text("Nothing carried", left + 20.0, offset, 22.0, GRAY);
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let (prefix, item) = match entry {
+                InventoryEntry::Equipped(_, item) => ("Worn: ", item),
+                InventoryEntry::Carried(_, item) => ("", item),
+            };
+            let color = if i == self.selection { YELLOW } else { WHITE };
+            draw_text(
+                &format!("{prefix}{}", item.describe()),
+                left + 20.0,
+                offset,
+                22.0,
+                color,
+            );
+            offset += 24.0;
+        }
+
+        draw_text(
+            "Enter - Use  E - Equip/Remove  D - Drop  Escape - Close",
+            left + 20.0,
+            top + height - 20.0,
+            18.0,
+            GRAY,
+        );
+    }
+}
@@ -1,13 +1,82 @@
+use crate::mission::*;
 use crate::prelude::*;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Trinket,
+}
+
+// What wearing an item adds, taken away again when it comes off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Equipment {
+    pub slot: EquipSlot,
+    #[serde(default)]
+    pub damage: i32,
+    #[serde(default)]
+    pub defense: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub name: String,
     pub sprite: Point,
+
+    // Used up as soon as it is picked up instead of being carried
+    #[serde(default)]
+    pub on_pickup: Option<Effect>,
+
+    // Applied to whoever uses it from the inventory
+    #[serde(default)]
+    pub on_use: Option<Effect>,
+
+    // Uses left before it is gone, None if it never runs out
+    #[serde(default)]
+    pub charges: Option<u32>,
+
+    #[serde(default)]
+    pub equip: Option<Equipment>,
 }
 
 impl Item {
     pub fn render(&self, screen: &Screen, position: Point) {
         screen.draw_tiny_sprite(TileSet::Items, position, self.sprite);
     }
+
+    pub fn describe(&self) -> String {
+        match (&self.charges, &self.equip) {
+            (Some(charges), _) => format!("{} ({charges})", self.name),
+            (None, Some(equipment)) => format!("{} [{:?}]", self.name, equipment.slot),
+            (None, None) => self.name.clone(),
+        }
+    }
+}
+
+// What a character might leave behind where it dies. Items are named rather
+// than copied onto every monster, and looked up in the data when one drops
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LootTable {
+    // Chance (0.0 - 1.0) that anything drops at all
+    pub drop_chance: f64,
+    pub items: Vec<LootEntry>,
+}
+
+impl LootTable {
+    // The name of the item dropped, if any
+    pub fn roll(&self, rng: &GameRng) -> Option<&str> {
+        let total: u32 = self.items.iter().map(|entry| entry.weight).sum();
+        if total == 0 || !rng.gen_chance(self.drop_chance) {
+            return None;
+        }
+
+        let mut pick = rng.gen_range(0, total as i32) as u32;
+        for entry in &self.items {
+            if pick < entry.weight {
+                return Some(&entry.item);
+            }
+            pick -= entry.weight;
+        }
+        None
+    }
 }
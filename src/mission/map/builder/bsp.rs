@@ -0,0 +1,121 @@
+use std::cmp::{max, min};
+
+use macroquad::rand::ChooseRandom;
+
+use crate::mission::*;
+use crate::prelude::*;
+
+pub struct BspMapBuilder;
+
+impl InitialMapBuilder for BspMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        loop {
+            build.map = Map::new_filled(build.map.theme);
+            let area = Rect::new(1, SCREEN_WIDTH - 1, 1, SCREEN_HEIGHT - 1);
+            let rooms = Self::split(build, rng, area, BSP_SPLIT_DEPTH);
+            fix_map_border(&mut build.map, rng);
+
+            if check_map_connectivity(&build.map, rooms[0].center()) {
+                build.rooms = Some(rooms);
+                break;
+            }
+        }
+    }
+}
+
+impl BspMapBuilder {
+    // Cuts the area in two until it is too small or deep enough, digs a room in each
+    // leaf and joins the two halves of every split on the way back up the tree
+    fn split(build: &mut BuilderMap, rng: &mut RandGenerator, area: Rect, depth: u32) -> Vec<Rect> {
+        let width = area.x2 - area.x1;
+        let height = area.y2 - area.y1;
+        let can_split_wide = width >= BSP_MIN_AREA_SIZE * 2;
+        let can_split_tall = height >= BSP_MIN_AREA_SIZE * 2;
+
+        if depth == 0 || (!can_split_wide && !can_split_tall) {
+            return vec![Self::dig_room(build, rng, &area)];
+        }
+
+        let split_wide = if can_split_wide && can_split_tall {
+            width > height || (width == height && rng.gen_range(0, 2) == 0)
+        } else {
+            can_split_wide
+        };
+
+        let (first, second) = if split_wide {
+            let x = rng.gen_range(area.x1 + BSP_MIN_AREA_SIZE, area.x2 - BSP_MIN_AREA_SIZE + 1);
+            (
+                Rect::new(area.x1, x, area.y1, area.y2),
+                Rect::new(x, area.x2, area.y1, area.y2),
+            )
+        } else {
+            let y = rng.gen_range(area.y1 + BSP_MIN_AREA_SIZE, area.y2 - BSP_MIN_AREA_SIZE + 1);
+            (
+                Rect::new(area.x1, area.x2, area.y1, y),
+                Rect::new(area.x1, area.x2, y, area.y2),
+            )
+        };
+
+        let mut rooms = Self::split(build, rng, first, depth - 1);
+        let other_rooms = Self::split(build, rng, second, depth - 1);
+
+        let start = rooms.choose_with_state(rng).expect("Every area has a room");
+        let end = other_rooms
+            .choose_with_state(rng)
+            .expect("Every area has a room");
+        Self::dig_corridor(build, rng, start.center(), end.center());
+
+        rooms.extend(other_rooms);
+        rooms
+    }
+
+    // Leaves at least a tile of wall on every side, so neighboring rooms never merge
+    fn dig_room(build: &mut BuilderMap, rng: &mut RandGenerator, area: &Rect) -> Rect {
+        let width = rng.gen_range(3, area.x2 - area.x1 - 1);
+        let height = rng.gen_range(3, area.y2 - area.y1 - 1);
+        let room = Rect::with_size(
+            rng.gen_range(area.x1 + 1, area.x2 - width),
+            rng.gen_range(area.y1 + 1, area.y2 - height),
+            width,
+            height,
+        );
+        room.for_each(|p| build.map.set(p, MapTile::floor(rng)));
+        build.take_snapshot();
+        room
+    }
+
+    fn dig_corridor(build: &mut BuilderMap, rng: &mut RandGenerator, start: Point, end: Point) {
+        for x in min(start.x, end.x)..=max(start.x, end.x) {
+            build.map.set(Point::new(x, start.y), MapTile::floor(rng));
+        }
+        for y in min(start.y, end.y)..=max(start.y, end.y) {
+            build.map.set(Point::new(end.x, y), MapTile::floor(rng));
+        }
+        build.take_snapshot();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn bsp_map_connected() {
+        let player = create_test_player();
+
+        for seed in 0..10 {
+            let mut rng = RandGenerator::new();
+            rng.srand(seed);
+
+            let level = BuilderChain::new(1, BspMapBuilder)
+                .with_room_start()
+                .with_level_features()
+                .build(&mut rng)
+                .into_level(player.clone());
+            assert!(level.items.iter().any(|(_, i)| i.name == "Runestone"));
+            assert!(level.characters.len() > 1);
+            assert_level_connected(&level);
+        }
+    }
+}
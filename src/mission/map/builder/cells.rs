@@ -3,54 +3,34 @@ use macroquad::rand::RandGenerator;
 use crate::mission::*;
 use crate::prelude::*;
 
-pub struct CellsMapBuilder {
-    map: Map,
-    data: Data,
-}
-
-impl CellsMapBuilder {
-    pub fn build(rng: &mut RandGenerator, difficulty: u32, player: Character) -> LevelState {
-        let mut builder = CellsMapBuilder {
-            map: Map::new(MapTheme::random(rng)),
-            data: Data::load().expect("Able to load data"),
-        };
+pub struct CellsMapBuilder;
 
+impl InitialMapBuilder for CellsMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
         loop {
-            builder.randomize_map(rng);
+            Self::randomize_map(&mut build.map, rng);
+            build.take_snapshot();
             for _ in 0..10 {
-                builder.iterate(rng);
+                Self::iterate(&mut build.map, rng);
+                build.take_snapshot();
             }
-            fix_map_border(&mut builder.map, rng);
+            fix_map_border(&mut build.map, rng);
 
-            let center = find_map_center(&builder.map);
-            if check_map_connectivity(&builder.map, center) {
+            let center = find_map_center(&build.map);
+            if check_map_connectivity(&build.map, center) {
                 break;
-            } else {
-                builder.clear();
             }
         }
-
-        let center = find_map_center(&builder.map);
-
-        let mut characters =
-            spawn_monster_randomly(rng, &builder.map, 30, center, difficulty, &builder.data);
-        setup_entrance(player, &mut characters, &mut builder.map, center);
-
-        let items = spawn_rune_far_away(&builder.map, center, &builder.data);
-
-        LevelState::new(builder.map, characters, items)
-    }
-
-    fn clear(&mut self) {
-        self.map = Map::new(self.map.theme);
     }
+}
 
-    fn iterate(&mut self, rng: &mut RandGenerator) {
-        let mut next_map = self.map.clone();
+impl CellsMapBuilder {
+    fn iterate(map: &mut Map, rng: &mut RandGenerator) {
+        let mut next_map = map.clone();
         for x in 1..SCREEN_WIDTH - 1 {
             for y in 1..SCREEN_HEIGHT - 1 {
                 let position = Point::new(x, y);
-                let neighbors = Self::neighbors(&self, position);
+                let neighbors = Self::neighbors(map, position);
                 if neighbors > 4 || neighbors == 0 {
                     next_map.set(position, MapTile::wall(rng));
                 } else {
@@ -58,19 +38,19 @@ impl CellsMapBuilder {
                 }
             }
         }
-        self.map = next_map;
+        *map = next_map;
     }
 
-    fn neighbors(&self, position: Point) -> usize {
+    fn neighbors(map: &Map, position: Point) -> usize {
         position
             .adjacent()
             .into_iter()
-            .filter(|p| self.map.get(*p).kind == TileKind::Wall)
+            .filter(|p| map.get(*p).kind == TileKind::Wall)
             .count()
     }
 
-    fn randomize_map(&mut self, rng: &mut RandGenerator) {
-        for tile in self.map.tiles.iter_mut() {
+    fn randomize_map(map: &mut Map, rng: &mut RandGenerator) {
+        for tile in map.tiles.iter_mut() {
             if rng.gen_range(0, 100) > 55 {
                 tile.kind = TileKind::Floor;
             } else {
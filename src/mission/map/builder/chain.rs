@@ -0,0 +1,229 @@
+use crate::mission::*;
+use crate::prelude::*;
+
+// Everything the steps of a chain have worked out about the level so far
+pub struct BuilderMap {
+    pub map: Map,
+    pub difficulty: u32,
+    pub data: Data,
+    // Where the player comes in, once a step has picked it
+    pub start: Option<Point>,
+    // Only set by builders that dig out rooms, the first one holds the start
+    pub rooms: Option<Vec<Rect>>,
+    // Groups of floors that monsters are spread over
+    pub spawn_regions: Vec<Vec<Point>>,
+    // Steps to walk from the start to each tile, row by row, None if it can not be reached
+    pub distances: Vec<Option<i32>>,
+    pub characters: Vec<Character>,
+    pub items: Vec<(Point, Item)>,
+    // Copies of the map as it was being built, for looking at generation step by step
+    pub snapshots: Vec<Map>,
+    record_snapshots: bool,
+}
+
+impl BuilderMap {
+    pub fn new(map: Map, difficulty: u32) -> Self {
+        Self {
+            map,
+            difficulty,
+            data: Data::load().expect("Able to load data"),
+            start: None,
+            rooms: None,
+            spawn_regions: vec![],
+            distances: vec![],
+            characters: vec![],
+            items: vec![],
+            snapshots: vec![],
+            record_snapshots: false,
+        }
+    }
+
+    pub fn take_snapshot(&mut self) {
+        if self.record_snapshots {
+            self.snapshots.push(self.map.clone());
+        }
+    }
+
+    pub fn start(&self) -> Point {
+        self.start.expect("A step should set the start first")
+    }
+
+    pub fn distance(&self, position: Point) -> Option<i32> {
+        if !self.map.in_bounds(position) {
+            return None;
+        }
+        self.distances
+            .get((position.y * SCREEN_WIDTH + position.x) as usize)
+            .copied()
+            .flatten()
+    }
+
+    pub fn into_level(self, mut player: Character) -> LevelState {
+        player.position = self.start();
+        let player_id = player.id;
+        let mut level = LevelState::new(self.map, player_id, vec![player], self.items);
+        for character in self.characters {
+            level.add_character(character);
+        }
+        level
+    }
+}
+
+// Digs out the layout of a level, starting from solid rock
+pub trait InitialMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap);
+}
+
+// Works on top of what earlier steps made: picking the start, spawning, stamping rooms...
+pub trait MetaMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap);
+}
+
+pub struct BuilderChain {
+    starter: Box<dyn InitialMapBuilder>,
+    builders: Vec<Box<dyn MetaMapBuilder>>,
+    difficulty: u32,
+    record_snapshots: bool,
+}
+
+impl BuilderChain {
+    pub fn new(difficulty: u32, starter: impl InitialMapBuilder + 'static) -> Self {
+        Self {
+            starter: Box::new(starter),
+            builders: vec![],
+            difficulty,
+            record_snapshots: false,
+        }
+    }
+
+    pub fn with(mut self, builder: impl MetaMapBuilder + 'static) -> Self {
+        self.builders.push(Box::new(builder));
+        self
+    }
+
+    pub fn with_snapshots(mut self) -> Self {
+        self.record_snapshots = true;
+        self
+    }
+
+    // Starts in the first room, with a monster in each of the others
+    pub fn with_room_start(self) -> Self {
+        self.with(RoomStart)
+            .with(RoomSpawnRegions)
+            .with(SpawnMonsters::OnePerRegion)
+    }
+
+    // Starts near the middle, with monsters scattered over the rest of the cave
+    pub fn with_cave_start(self) -> Self {
+        self.with(MapCenterStart)
+            .with(DistantSpawnRegion)
+            .with(SpawnMonsters::Scattered(30))
+    }
+
+    // What every level gets once the layout is dug out, the start picked and monsters spawned
+    pub fn with_level_features(self) -> Self {
+        self.with(DistanceMap)
+            .with(Entrance)
+            .with(PlaceRunestone)
+            .with(PlaceLoot)
+            .with(PlacePrefab)
+            .with(PlaceVault)
+            // Prefabs and vaults need the Runestone and loot placed first, but change the floors
+            .with(DistanceMap)
+    }
+
+    pub fn build(mut self, rng: &mut RandGenerator) -> BuilderMap {
+        let mut build = BuilderMap::new(Map::new_filled(MapTheme::random(rng)), self.difficulty);
+        build.record_snapshots = self.record_snapshots;
+
+        self.starter.build_map(rng, &mut build);
+        build.take_snapshot();
+        for builder in self.builders.iter_mut() {
+            builder.build_map(rng, &mut build);
+            build.take_snapshot();
+        }
+        build
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn chain_fills_in_metadata() {
+        let mut rng = RandGenerator::new();
+        rng.srand(3);
+
+        let build = BuilderChain::new(1, RoomsMapBuilder::new())
+            .with(RoomDoors)
+            .with(RoomStart)
+            .with(RoomSpawnRegions)
+            .with(SpawnMonsters::OnePerRegion)
+            .with(DistanceMap)
+            .with(Entrance)
+            .with(PlaceRunestone)
+            .build(&mut rng);
+
+        let start = build.start();
+        assert_eq!(build.map.get(start).kind, TileKind::Exit);
+        assert_eq!(build.distance(start), Some(0));
+        let rooms = build.rooms.as_ref().unwrap();
+        assert!(rooms[0].contains(start));
+        assert_eq!(build.spawn_regions.len(), rooms.len() - 1);
+        assert_eq!(build.characters.len(), rooms.len() - 1);
+
+        // The Runestone waits in the room the longest walk away
+        let (rune, _) = build
+            .items
+            .iter()
+            .find(|(_, i)| i.name == "Runestone")
+            .unwrap();
+        let farthest = rooms
+            .iter()
+            .filter_map(|r| build.distance(r.center()))
+            .max()
+            .unwrap();
+        assert_eq!(build.distance(*rune), Some(farthest));
+    }
+
+    #[test]
+    fn snapshots_only_when_asked() {
+        let mut rng = RandGenerator::new();
+        rng.srand(4);
+        let build = BuilderChain::new(1, CellsMapBuilder)
+            .with_cave_start()
+            .build(&mut rng);
+        assert!(build.snapshots.is_empty());
+
+        rng.srand(4);
+        let build = BuilderChain::new(1, CellsMapBuilder)
+            .with_cave_start()
+            .with_snapshots()
+            .build(&mut rng);
+        // The random fill, every smoothing pass and then one per step of the chain
+        assert!(build.snapshots.len() >= 11 + 4);
+        assert_eq!(
+            build.snapshots.last().unwrap().get(build.start()).kind,
+            TileKind::Floor
+        );
+    }
+
+    #[test]
+    fn distances_cover_stamped_rooms() {
+        for seed in 0..8 {
+            let mut rng = RandGenerator::new();
+            rng.srand(seed);
+            let mut build = BuilderChain::new(3, RoomsMapBuilder::new())
+                .with(RoomDoors)
+                .with_room_start()
+                .with_level_features()
+                .build(&mut rng);
+
+            let distances = build.distances.clone();
+            DistanceMap.build_map(&mut rng, &mut build);
+            assert_eq!(distances, build.distances);
+        }
+    }
+}
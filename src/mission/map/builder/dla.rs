@@ -0,0 +1,150 @@
+use crate::mission::*;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DlaSymmetry {
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl DlaSymmetry {
+    pub fn random(rng: &mut RandGenerator) -> Self {
+        match rng.gen_range(0, 4) {
+            0 => DlaSymmetry::None,
+            1 => DlaSymmetry::Horizontal,
+            2 => DlaSymmetry::Vertical,
+            _ => DlaSymmetry::Both,
+        }
+    }
+}
+
+pub struct DlaMapBuilder {
+    symmetry: DlaSymmetry,
+    brush_size: i32,
+}
+
+impl InitialMapBuilder for DlaMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        self.grow(build, rng);
+    }
+}
+
+impl DlaMapBuilder {
+    pub fn new(symmetry: DlaSymmetry, brush_size: i32) -> Self {
+        Self {
+            symmetry,
+            brush_size: brush_size.max(1),
+        }
+    }
+
+    pub fn random(rng: &mut RandGenerator) -> Self {
+        let symmetry = DlaSymmetry::random(rng);
+        Self::new(symmetry, rng.gen_range(1, 3))
+    }
+
+    // Particles wander through the rock until they bump into the cave, and stick
+    // to it where they were standing
+    fn grow(&self, build: &mut BuilderMap, rng: &mut RandGenerator) {
+        let center = Point::new(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2);
+        for x in -1..=1 {
+            for y in -1..=1 {
+                self.paint(&mut build.map, rng, center + Point::new(x, y));
+            }
+        }
+
+        let mut particles: u32 = 0;
+        while find_all_floors(&build.map).len() < DLA_DESIRED_FLOOR_AMOUNT as usize {
+            let mut position = Point::new(
+                rng.gen_range(1, SCREEN_WIDTH - 1),
+                rng.gen_range(1, SCREEN_HEIGHT - 1),
+            );
+            let mut previous = position;
+
+            while build.map.get(position).kind == TileKind::Wall {
+                previous = position;
+                let next = match rng.gen_range(0, 4) {
+                    0 => position + Point::new(-1, 0),
+                    1 => position + Point::new(1, 0),
+                    2 => position + Point::new(0, -1),
+                    _ => position + Point::new(0, 1),
+                };
+                if is_inside_border(next) {
+                    position = next;
+                }
+            }
+            self.paint(&mut build.map, rng, previous);
+
+            particles += 1;
+            if particles.is_multiple_of(10) {
+                build.take_snapshot();
+            }
+        }
+    }
+
+    // Digs out a brush sized square, mirrored across the middle of the map
+    fn paint(&self, map: &mut Map, rng: &mut RandGenerator, position: Point) {
+        let mirror_x = SCREEN_WIDTH - 1 - position.x;
+        let mirror_y = SCREEN_HEIGHT - 1 - position.y;
+        let targets = match self.symmetry {
+            DlaSymmetry::None => vec![position],
+            DlaSymmetry::Horizontal => vec![position, Point::new(mirror_x, position.y)],
+            DlaSymmetry::Vertical => vec![position, Point::new(position.x, mirror_y)],
+            DlaSymmetry::Both => vec![
+                position,
+                Point::new(mirror_x, position.y),
+                Point::new(position.x, mirror_y),
+                Point::new(mirror_x, mirror_y),
+            ],
+        };
+
+        // Larger brushes grow around the particle rather than off to one side
+        let offset = (self.brush_size - 1) / 2;
+        for target in targets {
+            for x in 0..self.brush_size {
+                for y in 0..self.brush_size {
+                    let p = target + Point::new(x - offset, y - offset);
+                    if is_inside_border(p) {
+                        map.set(p, MapTile::floor(rng));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_inside_border(p: Point) -> bool {
+    p.x > 0 && p.y > 0 && p.x < SCREEN_WIDTH - 1 && p.y < SCREEN_HEIGHT - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn dla_map_connected() {
+        let player = create_test_player();
+
+        let configs = [
+            (DlaSymmetry::None, 1),
+            (DlaSymmetry::Horizontal, 2),
+            (DlaSymmetry::Vertical, 1),
+            (DlaSymmetry::Both, 3),
+        ];
+        for (seed, (symmetry, brush_size)) in configs.into_iter().enumerate() {
+            let mut rng = RandGenerator::new();
+            rng.srand(seed as u64);
+
+            let level = BuilderChain::new(1, DlaMapBuilder::new(symmetry, brush_size))
+                .with(FixBorder)
+                .with_cave_start()
+                .with_level_features()
+                .build(&mut rng)
+                .into_level(player.clone());
+            assert!(find_all_floors(&level.map).len() >= DLA_DESIRED_FLOOR_AMOUNT as usize - 1);
+            assert_level_connected(&level);
+        }
+    }
+}
@@ -3,65 +3,51 @@ use macroquad::rand::ChooseRandom;
 use crate::mission::*;
 use crate::prelude::*;
 
-pub struct DrunkDigger {
-    map: Map,
-    data: Data,
-}
-
-impl DrunkDigger {
-    pub fn build(rng: &mut RandGenerator, difficulty: u32, player: Character) -> LevelState {
-        let mut builder = DrunkDigger {
-            map: Map::new_filled(MapTheme::random(rng)),
-            data: Data::load().expect("Able to load data"),
-        };
-
-        builder.dig(rng);
-        fix_map_border(&mut builder.map, rng);
-
-        let center = find_map_center(&builder.map);
+pub struct DrunkDigger;
 
-        let mut characters =
-            spawn_monster_randomly(rng, &builder.map, 30, center, difficulty, &builder.data);
-        setup_entrance(player, &mut characters, &mut builder.map, center);
-
-        let items = spawn_rune_far_away(&builder.map, center, &builder.data);
-
-        LevelState::new(builder.map, characters, items)
+impl InitialMapBuilder for DrunkDigger {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        Self::dig(build, rng);
     }
+}
 
-    fn dig(&mut self, rng: &mut RandGenerator) {
+impl DrunkDigger {
+    fn dig(build: &mut BuilderMap, rng: &mut RandGenerator) {
         let start = Point::new(
             rng.gen_range(0, SCREEN_WIDTH),
             rng.gen_range(0, SCREEN_HEIGHT),
         );
-        self.stagger(start, rng);
+        Self::stagger(&mut build.map, start, rng);
+        build.take_snapshot();
 
         loop {
-            let floors = find_all_floors(&self.map);
+            let floors = find_all_floors(&build.map);
             if floors.len() >= DRUNK_DESIRED_FLOOR_AMOUNT as usize {
                 break;
             }
 
-            self.stagger(
+            Self::stagger(
+                &mut build.map,
                 *floors.choose_with_state(rng).expect("At least one floor"),
                 rng,
             );
+            build.take_snapshot();
         }
     }
 
-    fn stagger(&mut self, start: Point, rng: &mut RandGenerator) {
+    fn stagger(map: &mut Map, start: Point, rng: &mut RandGenerator) {
         let mut position = start;
         let mut distance = 0;
 
         loop {
-            self.map.set(position, MapTile::floor(rng));
+            map.set(position, MapTile::floor(rng));
             match rng.gen_range(0, 4) {
                 0 => position.x -= 1,
                 1 => position.x += 1,
                 2 => position.y -= 1,
                 _ => position.y += 1,
             }
-            if !self.map.in_bounds(position) {
+            if !map.in_bounds(position) {
                 break;
             }
             distance += 1;
@@ -0,0 +1,225 @@
+use std::collections::VecDeque;
+
+use adam_fov_rs::GridPoint;
+use macroquad::rand::ChooseRandom;
+
+use crate::mission::enemy_set::get_enemy_set_for_difficulty;
+use crate::mission::*;
+use crate::prelude::*;
+
+// Monsters never start this close to the player
+const SPAWN_MIN_DISTANCE: usize = 10;
+const LEVEL_LOOT: [&str; 8] = [
+    "Healing Draught",
+    "Elixir of Might",
+    "Elixir of Protection",
+    "Rage Potion",
+    "Iron Sword",
+    "Chain Mail",
+    "Ring of Striking",
+    "Warding Amulet",
+];
+
+pub struct FixBorder;
+
+impl MetaMapBuilder for FixBorder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        fix_map_border(&mut build.map, rng);
+    }
+}
+
+// Starts on the floor closest to the middle of the map
+pub struct MapCenterStart;
+
+impl MetaMapBuilder for MapCenterStart {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        build.start = Some(find_map_center(&build.map));
+    }
+}
+
+pub struct RoomStart;
+
+impl MetaMapBuilder for RoomStart {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let rooms = build.rooms.as_ref().expect("RoomStart needs rooms");
+        build.start = Some(rooms[0].center());
+    }
+}
+
+pub struct DistanceMap;
+
+impl MetaMapBuilder for DistanceMap {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let index = |p: Point| (p.y * SCREEN_WIDTH + p.x) as usize;
+        let start = build.start();
+
+        let mut distances = vec![None; (SCREEN_WIDTH * SCREEN_HEIGHT) as usize];
+        distances[index(start)] = Some(0);
+        let mut to_visit = VecDeque::from([start]);
+        while let Some(current) = to_visit.pop_front() {
+            let distance = distances[index(current)].expect("Visited tiles have a distance");
+            for adj in current.adjacent() {
+                if build.map.in_bounds(adj)
+                    && build.map.get(adj).can_enter()
+                    && distances[index(adj)].is_none()
+                {
+                    distances[index(adj)] = Some(distance + 1);
+                    to_visit.push_back(adj);
+                }
+            }
+        }
+        build.distances = distances;
+    }
+}
+
+// Every room but the one the player starts in
+pub struct RoomSpawnRegions;
+
+impl MetaMapBuilder for RoomSpawnRegions {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let rooms = build.rooms.as_ref().expect("RoomSpawnRegions needs rooms");
+        build.spawn_regions = rooms
+            .iter()
+            .skip(1)
+            .map(|room| {
+                let mut floors = vec![];
+                room.for_each(|p| {
+                    if build.map.get(p).kind == TileKind::Floor {
+                        floors.push(p);
+                    }
+                });
+                floors
+            })
+            .filter(|floors| !floors.is_empty())
+            .collect();
+    }
+}
+
+// One big region of every floor not too close to the start
+pub struct DistantSpawnRegion;
+
+impl MetaMapBuilder for DistantSpawnRegion {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let start = build.start();
+        build.spawn_regions = vec![
+            find_all_floors(&build.map)
+                .into_iter()
+                .filter(|f| f.king_dist(start) > SPAWN_MIN_DISTANCE)
+                .collect(),
+        ];
+    }
+}
+
+pub enum SpawnMonsters {
+    OnePerRegion,
+    // Spread over all regions, never two on the same tile
+    Scattered(usize),
+}
+
+impl MetaMapBuilder for SpawnMonsters {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let enemies = get_enemy_set_for_difficulty(&build.data, build.difficulty);
+        let positions = match self {
+            SpawnMonsters::OnePerRegion => build
+                .spawn_regions
+                .iter()
+                .filter_map(|region| region.choose_with_state(rng).copied())
+                .collect::<Vec<_>>(),
+            SpawnMonsters::Scattered(count) => build
+                .spawn_regions
+                .concat()
+                .choose_multiple_with_state(rng, *count)
+                .copied()
+                .collect(),
+        };
+
+        for position in positions {
+            let name = enemies.choose_with_state(rng).unwrap();
+            let mut enemy = build.data.get_character(name);
+            enemy.position = position;
+            build.characters.push(enemy);
+        }
+    }
+}
+
+pub struct Entrance;
+
+impl MetaMapBuilder for Entrance {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        build.map.set(
+            build.start(),
+            MapTile {
+                kind: TileKind::Exit,
+                known: true,
+                variation: 0,
+            },
+        );
+    }
+}
+
+// The longest walk from the start, into the farthest room for room based maps
+pub struct PlaceRunestone;
+
+impl MetaMapBuilder for PlaceRunestone {
+    fn build_map(&mut self, _rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let candidates = match &build.rooms {
+            Some(rooms) => rooms.iter().map(|r| r.center()).collect(),
+            None => find_all_floors(&build.map),
+        };
+        let farthest = candidates
+            .into_iter()
+            .max_by_key(|p| build.distance(*p))
+            .expect("One should be farthest");
+        build
+            .items
+            .push((farthest, build.data.get_item("Runestone")));
+    }
+}
+
+// A piece of loot per difficulty lying around, somewhere it can be walked to
+pub struct PlaceLoot;
+
+impl MetaMapBuilder for PlaceLoot {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let floors = find_all_floors(&build.map)
+            .into_iter()
+            .filter(|f| build.distance(*f).is_some_and(|d| d > 0))
+            .filter(|f| !build.items.iter().any(|(p, _)| p == f))
+            .collect::<Vec<_>>();
+        let positions = floors
+            .choose_multiple_with_state(rng, build.difficulty as usize)
+            .copied()
+            .collect::<Vec<_>>();
+
+        for position in positions {
+            let name = LEVEL_LOOT.choose_with_state(rng).unwrap();
+            build.items.push((position, build.data.get_item(name)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn distance_map_walks_around_walls() {
+        let mut rng = RandGenerator::new();
+        let mut map = Map::new_filled(MapTheme::Stone);
+        // A U shaped corridor, the ends are close but the walk between them is long
+        for y in 1..6 {
+            map.set(Point::new(1, y), MapTile::floor(&mut rng));
+            map.set(Point::new(3, y), MapTile::floor(&mut rng));
+        }
+        map.set(Point::new(2, 5), MapTile::floor(&mut rng));
+
+        let mut build = BuilderMap::new(map, 1);
+        build.start = Some(Point::new(1, 1));
+        DistanceMap.build_map(&mut rng, &mut build);
+
+        assert_eq!(build.distance(Point::new(1, 1)), Some(0));
+        assert_eq!(build.distance(Point::new(3, 1)), Some(8));
+        assert_eq!(build.distance(Point::new(2, 1)), None);
+    }
+}
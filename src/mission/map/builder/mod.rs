@@ -1,13 +1,17 @@
-use crate::mission::enemy_set::get_enemy_set_for_difficulty;
 use crate::mission::*;
 use crate::prelude::*;
 
 use adam_fov_rs::GridPoint;
 
 mod utils;
-use macroquad::rand::ChooseRandom;
 pub use utils::*;
 
+mod chain;
+pub use chain::*;
+
+mod meta;
+pub use meta::*;
+
 mod rooms;
 pub use rooms::*;
 
@@ -17,45 +21,76 @@ pub use cells::*;
 mod drunk_digger;
 pub use drunk_digger::*;
 
+mod bsp;
+pub use bsp::*;
+
+mod wfc;
+pub use wfc::*;
+
+mod dla;
+pub use dla::*;
+
+mod vault;
+pub use vault::*;
+
+mod prefab;
+pub use prefab::*;
+
 pub mod enemy_set;
 
-pub fn generate_random_map(player: Character, difficulty: u32) -> LevelState {
-    let mut rng = RandGenerator::new();
-    let seed = macroquad::miniquad::date::now() as u64;
+pub const BUILDER_KINDS: [&str; 6] = ["Rooms", "Cells", "BSP", "WFC", "DLA", "Drunk Digger"];
 
+pub fn random_builder_kind(rng: &mut RandGenerator) -> usize {
+    rng.gen_range(0, BUILDER_KINDS.len() as i32) as usize
+}
+
+// The generator for a kind from BUILDER_KINDS, with the steps that suit it
+pub fn builder_chain(kind: usize, rng: &mut RandGenerator, difficulty: u32) -> BuilderChain {
+    let chain = match kind {
+        0 => BuilderChain::new(difficulty, RoomsMapBuilder::new())
+            .with(RoomDoors)
+            .with_room_start(),
+        1 => BuilderChain::new(difficulty, CellsMapBuilder).with_cave_start(),
+        2 => BuilderChain::new(difficulty, BspMapBuilder).with_room_start(),
+        3 => BuilderChain::new(difficulty, WfcMapBuilder).with_cave_start(),
+        4 => BuilderChain::new(difficulty, DlaMapBuilder::random(rng))
+            .with(FixBorder)
+            .with_cave_start(),
+        _ => BuilderChain::new(difficulty, DrunkDigger)
+            .with(FixBorder)
+            .with_cave_start(),
+    };
+    chain.with_level_features()
+}
+
+pub fn generate_random_map(player: Character, difficulty: u32, seed: u64) -> LevelState {
     if cfg!(debug_assertions) {
         println!("Generating map with seed {seed}");
     }
 
-    rng.srand(seed);
+    let mission_rng = GameRng::new(seed);
+    let mut rng = mission_rng.generator();
 
-    let level = match rng.gen_range(0, 3) {
-        0 => RoomsMapBuilder::build(&mut rng, difficulty, player),
-        1 => CellsMapBuilder::build(&mut rng, difficulty, player),
-        _ => DrunkDigger::build(&mut rng, difficulty, player),
-    };
+    let kind = random_builder_kind(&mut rng);
+    let mut level = builder_chain(kind, &mut rng, difficulty)
+        .build(&mut rng)
+        .into_level(player);
 
     // level.map.dump_map_to_console();
+    level.rng = mission_rng;
     level
 }
 
-pub fn setup_entrance(
-    mut player: Character,
-    characters: &mut Vec<Character>,
-    map: &mut Map,
-    center: Point,
-) {
-    player.position = center;
-    characters.push(player);
-
-    map.set(
-        center,
-        MapTile {
-            kind: TileKind::Exit,
-            known: true,
-            variation: 0,
-        },
-    );
+// Floors above the deepest one lead further down from where the Runestone would have been
+pub fn generate_floor(player: Character, difficulty: u32, seed: u64, deepest: bool) -> LevelState {
+    let mut level = generate_random_map(player, difficulty, seed);
+    if !deepest && let Some(index) = level.items.iter().position(|(_, i)| i.name == "Runestone") {
+        let (position, _) = level.items.remove(index);
+        let mut tile = level.map.get(position);
+        tile.kind = TileKind::StairsDown;
+        level.map.set(position, tile);
+    }
+    level
 }
 
 pub fn find_all_floors(map: &Map) -> Vec<Point> {
@@ -71,42 +106,6 @@ pub fn find_all_floors(map: &Map) -> Vec<Point> {
     floors
 }
 
-pub fn spawn_monster_randomly(
-    rng: &mut RandGenerator,
-    map: &Map,
-    count: usize,
-    center: Point,
-    difficulty: u32,
-    data: &Data,
-) -> Vec<Character> {
-    let enemies = get_enemy_set_for_difficulty(&data, difficulty);
-
-    let floors = find_all_floors(map);
-    floors
-        .into_iter()
-        .filter(|f| f.king_dist(center) > 10)
-        .collect::<Vec<_>>()
-        .choose_multiple_with_state(rng, count)
-        .map(|position| {
-            let name = enemies.choose_with_state(rng).unwrap();
-            let mut enemy = data.get_character(name);
-            enemy.position = *position;
-            enemy
-        })
-        .collect()
-}
-
-pub fn spawn_rune_far_away(map: &Map, center: Point, data: &Data) -> Vec<(Point, Item)> {
-    let floors = find_all_floors(map);
-
-    let farthest = floors
-        .into_iter()
-        .max_by_key(|f| f.king_dist(center))
-        .expect("One should be closest");
-
-    vec![(farthest, data.get_item("Runestone"))]
-}
-
 pub fn fix_map_border(map: &mut Map, rng: &mut RandGenerator) {
     for x in 0..SCREEN_WIDTH {
         map.set(Point::new(x, 0), MapTile::wall(rng));
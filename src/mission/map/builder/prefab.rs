@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use adam_fov_rs::GridPoint;
+use macroquad::rand::ChooseRandom;
+
+use crate::mission::*;
+use crate::prelude::*;
+
+const PREFAB_PLACEMENT_ATTEMPTS: u32 = 200;
+
+// What a legend character stands for, always on a floor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PrefabTile {
+    Monster(String),
+    Item(String),
+}
+
+// A hand made room stamped over part of a generated map. Templates use
+// '#' for walls, '.' for floors and the legend for anything else
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prefab {
+    pub name: String,
+    pub template: Vec<String>,
+    #[serde(default)]
+    pub legend: HashMap<char, PrefabTile>,
+    #[serde(default)]
+    pub min_difficulty: u32,
+}
+
+impl Prefab {
+    pub fn width(&self) -> i32 {
+        self.template.iter().map(|r| r.len()).max().unwrap_or(0) as i32
+    }
+
+    pub fn height(&self) -> i32 {
+        self.template.len() as i32
+    }
+
+    // Offsets from the top left corner, with what is there
+    pub fn cells(&self) -> Vec<(Point, char)> {
+        self.template
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(move |(x, c)| (Point::new(x as i32, y as i32), c))
+            })
+            .collect()
+    }
+
+    pub fn has_runestone(&self) -> bool {
+        self.legend
+            .values()
+            .any(|t| matches!(t, PrefabTile::Item(name) if name == "Runestone"))
+    }
+}
+
+// Stamps one prefab fitting the difficulty somewhere away from the start, as long
+// as it only covers plain walls and floors and every floor stays reachable.
+// Monsters already standing there make way, and a Runestone chamber replaces the
+// Runestone placed before
+pub struct PlacePrefab;
+
+impl MetaMapBuilder for PlacePrefab {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let prefabs = build.data.get_prefabs(build.difficulty);
+        let Some(prefab) = prefabs.choose_with_state(rng) else {
+            return;
+        };
+        let (width, height) = (prefab.width(), prefab.height());
+        if width + 2 >= SCREEN_WIDTH || height + 2 >= SCREEN_HEIGHT {
+            return;
+        }
+        let start = build.start();
+
+        for _ in 0..PREFAB_PLACEMENT_ATTEMPTS {
+            let area = Rect::with_size(
+                rng.gen_range(1, SCREEN_WIDTH - width),
+                rng.gen_range(1, SCREEN_HEIGHT - height),
+                width,
+                height,
+            );
+            let corner = Point::new(area.x1, area.y1);
+
+            let mut fits = true;
+            area.for_each(|p| {
+                fits &= p.king_dist(start) > 10
+                    && matches!(build.map.get(p).kind, TileKind::Wall | TileKind::Floor)
+                    && !build.items.iter().any(|(i, _)| *i == p);
+            });
+            if !fits {
+                continue;
+            }
+
+            let before = build.map.clone();
+            for (offset, c) in prefab.cells() {
+                let tile = if c == '#' {
+                    MapTile::wall(rng)
+                } else {
+                    MapTile::floor(rng)
+                };
+                build.map.set(corner + offset, tile);
+            }
+            if !check_map_connectivity(&build.map, start) {
+                build.map = before;
+                continue;
+            }
+
+            build.characters.retain(|c| !area.contains(c.position));
+            if prefab.has_runestone() {
+                build.items.retain(|(_, i)| i.name != "Runestone");
+            }
+            for (offset, c) in prefab.cells() {
+                match prefab.legend.get(&c) {
+                    Some(PrefabTile::Monster(name)) => {
+                        let mut monster = build.data.get_character(name);
+                        monster.position = corner + offset;
+                        build.characters.push(monster);
+                    }
+                    Some(PrefabTile::Item(name)) => {
+                        let item = build.data.get_item(name);
+                        build.items.push((corner + offset, item));
+                    }
+                    None => {}
+                }
+            }
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn prefabs_use_known_data() {
+        let data = Data::load().unwrap();
+        let prefabs = data.get_prefabs(u32::MAX);
+        assert!(!prefabs.is_empty());
+
+        for prefab in prefabs {
+            for (_, c) in prefab.cells() {
+                assert!(
+                    c == '#' || c == '.' || prefab.legend.contains_key(&c),
+                    "{} uses {c} without a legend",
+                    prefab.name
+                );
+            }
+            for tile in prefab.legend.values() {
+                match tile {
+                    PrefabTile::Monster(name) => _ = data.get_character(name),
+                    PrefabTile::Item(name) => _ = data.get_item(name),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prefab_keeps_map_connected() {
+        let center = Point::new(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2);
+
+        for seed in 0..10 {
+            let mut rng = RandGenerator::new();
+            rng.srand(seed);
+
+            let mut map = Map::new_filled(MapTheme::Stone);
+            Rect::new(1, SCREEN_WIDTH - 1, 1, SCREEN_HEIGHT - 1)
+                .for_each(|p| map.set(p, MapTile::floor(&mut rng)));
+            let mut build = BuilderMap::new(map, 3);
+            build.start = Some(center);
+            DistanceMap.build_map(&mut rng, &mut build);
+            PlaceRunestone.build_map(&mut rng, &mut build);
+            PlacePrefab.build_map(&mut rng, &mut build);
+            let BuilderMap {
+                map,
+                items,
+                characters,
+                ..
+            } = build;
+
+            assert!(check_map_connectivity(&map, center));
+            assert!(
+                find_all_floors(&map).len() < ((SCREEN_WIDTH - 2) * (SCREEN_HEIGHT - 2)) as usize
+            );
+            let runestones = items.iter().filter(|(_, i)| i.name == "Runestone").count();
+            assert_eq!(runestones, 1);
+            for character in &characters {
+                assert_eq!(map.get(character.position).kind, TileKind::Floor);
+            }
+        }
+    }
+}
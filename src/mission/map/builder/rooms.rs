@@ -1,95 +1,39 @@
 use std::cmp::{max, min};
 
-use adam_fov_rs::GridPoint;
-use macroquad::rand::ChooseRandom;
-
-use crate::mission::enemy_set::get_enemy_set_for_difficulty;
 use crate::mission::*;
 use crate::prelude::*;
 
+#[derive(Default)]
 pub struct RoomsMapBuilder {
-    map: Map,
     rooms: Vec<Rect>,
-    data: Data,
 }
 
-impl RoomsMapBuilder {
-    pub fn build(rng: &mut RandGenerator, difficulty: u32, player: Character) -> LevelState {
-        let mut builder = RoomsMapBuilder {
-            map: Map::new(MapTheme::random(rng)),
-            rooms: vec![],
-            data: Data::load().expect("Able to load data"),
-        };
-
+impl InitialMapBuilder for RoomsMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
         loop {
-            builder.fill(TileKind::Wall);
-            builder.build_random_rooms(rng, 20);
-            builder.build_corridors(rng);
-            if check_map_connectivity(&builder.map, builder.rooms[0].center()) {
+            build.map = Map::new_filled(build.map.theme);
+            self.rooms = vec![];
+            self.build_random_rooms(build, rng, 20);
+            self.build_corridors(build, rng);
+            if check_map_connectivity(&build.map, self.rooms[0].center()) {
                 break;
-            } else {
-                builder.clear();
             }
         }
-
-        let mut characters = builder.spawn_monsters(rng, difficulty);
-
-        setup_entrance(
-            player,
-            &mut characters,
-            &mut builder.map,
-            builder.rooms[0].center(),
-        );
-
-        let items = builder.place_items();
-
-        LevelState::new(builder.map, characters, items)
-    }
-
-    fn clear(&mut self) {
-        self.map = Map::new(self.map.theme);
-        self.rooms = vec![];
-    }
-
-    fn place_items(&mut self) -> Vec<(Point, Item)> {
-        let start = self.rooms[0].center();
-
-        let farthest = self
-            .rooms
-            .iter()
-            .max_by_key(|r| r.center().king_dist(start));
-
-        vec![(
-            farthest.expect("Find farthest room").center(),
-            self.data.get_item("Runestone"),
-        )]
-    }
-
-    fn fill(&mut self, tile: TileKind) {
-        self.map.tiles.iter_mut().for_each(|t| {
-            *t = MapTile {
-                kind: tile,
-                known: false,
-                variation: 0,
-            }
-        });
+        build.rooms = Some(self.rooms.clone());
     }
+}
 
-    fn spawn_monsters(&self, rng: &mut RandGenerator, difficulty: u32) -> Vec<Character> {
-        let enemies = get_enemy_set_for_difficulty(&self.data, difficulty);
-        self.rooms
-            .iter()
-            .skip(1)
-            .map(|r| {
-                let name = enemies.choose_with_state(rng).unwrap();
-                let mut enemy = self.data.get_character(name);
-                enemy.position = r.center();
-                enemy
-            })
-            .collect()
+impl RoomsMapBuilder {
+    pub fn new() -> Self {
+        Self { rooms: vec![] }
     }
 
-    fn build_random_rooms(&mut self, rng: &mut RandGenerator, desired_room_count: usize) {
+    fn build_random_rooms(
+        &mut self,
+        build: &mut BuilderMap,
+        rng: &mut RandGenerator,
+        desired_room_count: usize,
+    ) {
         while self.rooms.len() < desired_room_count {
             let room = Rect::with_size(
                 rng.gen_range(1, SCREEN_WIDTH - 10),
@@ -99,26 +43,27 @@ impl RoomsMapBuilder {
             );
             if !self.rooms.iter().any(|r| r.intersect(&room)) {
                 room.for_each(|p| {
-                    self.map.set(p, MapTile::floor(rng));
+                    build.map.set(p, MapTile::floor(rng));
                 });
                 self.rooms.push(room);
+                build.take_snapshot();
             }
         }
     }
 
-    fn build_vert_tunnel(&mut self, y1: i32, y2: i32, x: i32, rng: &mut RandGenerator) {
+    fn build_vert_tunnel(map: &mut Map, y1: i32, y2: i32, x: i32, rng: &mut RandGenerator) {
         for y in min(y1, y2)..=max(y1, y2) {
-            self.map.set(Point::new(x, y), MapTile::floor(rng));
+            map.set(Point::new(x, y), MapTile::floor(rng));
         }
     }
 
-    fn build_horz_tunnel(&mut self, x1: i32, x2: i32, y: i32, rng: &mut RandGenerator) {
+    fn build_horz_tunnel(map: &mut Map, x1: i32, x2: i32, y: i32, rng: &mut RandGenerator) {
         for x in min(x1, x2)..=max(x1, x2) {
-            self.map.set(Point::new(x, y), MapTile::floor(rng));
+            map.set(Point::new(x, y), MapTile::floor(rng));
         }
     }
 
-    fn build_corridors(&mut self, rng: &mut RandGenerator) {
+    fn build_corridors(&self, build: &mut BuilderMap, rng: &mut RandGenerator) {
         let mut rooms = self.rooms.clone();
 
         rooms.sort_by(|a, b| a.center().x.cmp(&b.center().x));
@@ -127,32 +72,70 @@ impl RoomsMapBuilder {
             let previous_room_center = self.rooms[i - 1].center();
             let next_room_center = room.center();
             if rng.gen_range(0, 2) == 1 {
-                self.build_horz_tunnel(
+                Self::build_horz_tunnel(
+                    &mut build.map,
                     previous_room_center.x,
                     next_room_center.x,
                     previous_room_center.y,
                     rng,
                 );
-                self.build_vert_tunnel(
+                Self::build_vert_tunnel(
+                    &mut build.map,
                     previous_room_center.y,
                     next_room_center.y,
                     next_room_center.x,
                     rng,
                 );
             } else {
-                self.build_vert_tunnel(
+                Self::build_vert_tunnel(
+                    &mut build.map,
                     previous_room_center.y,
                     next_room_center.y,
                     next_room_center.x,
                     rng,
                 );
-                self.build_horz_tunnel(
+                Self::build_horz_tunnel(
+                    &mut build.map,
                     previous_room_center.x,
                     next_room_center.x,
                     previous_room_center.y,
                     rng,
                 );
             }
+            build.take_snapshot();
+        }
+    }
+}
+
+pub struct RoomDoors;
+
+impl MetaMapBuilder for RoomDoors {
+    // Corridors entering a room through a gap in its wall may get a door
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let rooms = build.rooms.clone().expect("RoomDoors needs rooms");
+        for room in rooms {
+            let mut entrances = vec![];
+            for x in room.x1..room.x2 {
+                entrances.push((Point::new(x, room.y1 - 1), Point::new(1, 0)));
+                entrances.push((Point::new(x, room.y2), Point::new(1, 0)));
+            }
+            for y in room.y1..room.y2 {
+                entrances.push((Point::new(room.x1 - 1, y), Point::new(0, 1)));
+                entrances.push((Point::new(room.x2, y), Point::new(0, 1)));
+            }
+
+            for (position, side) in entrances {
+                let is_wall =
+                    |p: Point| !build.map.in_bounds(p) || build.map.get(p).kind == TileKind::Wall;
+                if build.map.in_bounds(position)
+                    && build.map.get(position).kind == TileKind::Floor
+                    && is_wall(position - side)
+                    && is_wall(position + side)
+                    && rng.gen_range(0, 2) == 0
+                {
+                    build.map.set(position, MapTile::door(false));
+                }
+            }
         }
     }
 }
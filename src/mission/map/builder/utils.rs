@@ -31,6 +31,44 @@ pub fn check_map_connectivity(map: &Map, start: Point) -> bool {
     true
 }
 
+// Walls in every floor that can not be walked to from start
+pub fn cull_unreachable(map: &mut Map, start: Point, rng: &mut RandGenerator) {
+    let mut visited = HashSet::new();
+    let mut to_visit = vec![start];
+
+    while let Some(next) = to_visit.pop() {
+        if visited.insert(next) {
+            for adj in next.adjacent() {
+                if map.in_bounds(adj) && map.get(adj).can_enter() {
+                    to_visit.push(adj);
+                }
+            }
+        }
+    }
+
+    for position in map.tiles() {
+        if map.get(position).can_enter() && !visited.contains(&position) {
+            map.set(position, MapTile::wall(rng));
+        }
+    }
+}
+
+// The vault is the only part of a map meant to be behind a locked door,
+// so with those opened every floor should be reachable from the entrance
+#[cfg(test)]
+pub fn assert_level_connected(level: &LevelState) {
+    let start = level.get_player().unwrap().position;
+    assert_eq!(level.map.get(start).kind, TileKind::Exit);
+
+    let mut map = level.map.clone();
+    for p in map.tiles() {
+        if map.get(p).kind == TileKind::LockedDoor {
+            map.set(p, MapTile::door(true));
+        }
+    }
+    assert!(check_map_connectivity(&map, start));
+}
+
 #[cfg(test)]
 mod tests {
     use crate::mission::*;
@@ -0,0 +1,156 @@
+use macroquad::rand::ChooseRandom;
+
+use crate::mission::*;
+use crate::prelude::*;
+
+const VAULT_SIZE: i32 = 3;
+const VAULT_TUNNEL_LENGTH: i32 = 8;
+const VAULT_LOOT: [&str; 3] = ["Healing Draught", "Elixir of Might", "Elixir of Protection"];
+
+pub struct PlaceVault;
+
+impl MetaMapBuilder for PlaceVault {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let vault = place_vault(rng, &mut build.map, &build.data, &build.items);
+        build.items.extend(vault);
+    }
+}
+
+// Carves a small room out of solid rock, sealed by a locked door at the end of a
+// short tunnel back to the rest of the map. Returns the loot inside and the key,
+// which is dropped on a floor not already holding an item
+pub fn place_vault(
+    rng: &mut RandGenerator,
+    map: &mut Map,
+    data: &Data,
+    items: &[(Point, Item)],
+) -> Vec<(Point, Item)> {
+    let floors = find_all_floors(map)
+        .into_iter()
+        .filter(|f| !items.iter().any(|(p, _)| p == f))
+        .collect::<Vec<_>>();
+    let Some(key_position) = floors.choose_with_state(rng).copied() else {
+        return vec![];
+    };
+
+    for _ in 0..100 {
+        let vault = Rect::with_size(
+            rng.gen_range(2, SCREEN_WIDTH - VAULT_SIZE - 2),
+            rng.gen_range(2, SCREEN_HEIGHT - VAULT_SIZE - 2),
+            VAULT_SIZE,
+            VAULT_SIZE,
+        );
+        if !is_solid_rock(map, &vault) {
+            continue;
+        }
+
+        let mut directions = vec![
+            Point::new(0, -1),
+            Point::new(0, 1),
+            Point::new(-1, 0),
+            Point::new(1, 0),
+        ];
+        directions.shuffle_with_state(rng);
+
+        for direction in directions {
+            let door = vault.center() + direction + direction;
+            let Some(tunnel) = find_tunnel(map, door, direction) else {
+                continue;
+            };
+
+            vault.for_each(|p| map.set(p, MapTile::floor(rng)));
+            for p in tunnel {
+                map.set(p, MapTile::floor(rng));
+            }
+            map.set(
+                door,
+                MapTile {
+                    kind: TileKind::LockedDoor,
+                    known: false,
+                    variation: 0,
+                },
+            );
+
+            let mut inside = vec![];
+            vault.for_each(|p| inside.push(p));
+            let count = rng.gen_range(1, 3) as usize;
+            let mut loot = inside
+                .choose_multiple_with_state(rng, count)
+                .map(|p| {
+                    (
+                        *p,
+                        data.get_item(VAULT_LOOT.choose_with_state(rng).unwrap()),
+                    )
+                })
+                .collect::<Vec<_>>();
+            loot.push((key_position, data.get_item("Vault Key")));
+            return loot;
+        }
+    }
+    vec![]
+}
+
+// The vault and the ring of wall around it must not touch anything already dug out
+fn is_solid_rock(map: &Map, vault: &Rect) -> bool {
+    let mut solid = true;
+    Rect::new(vault.x1 - 1, vault.x2 + 1, vault.y1 - 1, vault.y2 + 1).for_each(|p| {
+        solid &= map.in_bounds(p) && map.get(p).kind == TileKind::Wall;
+    });
+    solid
+}
+
+// Walls to dig from just past the door until reaching an open tile
+fn find_tunnel(map: &Map, door: Point, direction: Point) -> Option<Vec<Point>> {
+    let mut tunnel = vec![];
+    let mut current = door + direction;
+    for _ in 0..VAULT_TUNNEL_LENGTH {
+        if current.x <= 0
+            || current.y <= 0
+            || current.x >= SCREEN_WIDTH - 1
+            || current.y >= SCREEN_HEIGHT - 1
+        {
+            return None;
+        }
+        match map.get(current).kind {
+            TileKind::Wall => tunnel.push(current),
+            TileKind::Floor | TileKind::Door { .. } => return Some(tunnel),
+            TileKind::Exit | TileKind::StairsDown | TileKind::LockedDoor => return None,
+        }
+        current = current + direction;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn vault_is_locked_off() {
+        let data = Data::load().unwrap();
+        let mut rng = RandGenerator::new();
+        rng.srand(7);
+
+        let mut map = Map::new_filled(MapTheme::Stone);
+        for x in 1..SCREEN_WIDTH - 1 {
+            map.set(Point::new(x, SCREEN_HEIGHT / 2), MapTile::floor(&mut rng));
+        }
+        let start = Point::new(1, SCREEN_HEIGHT / 2);
+
+        let items = place_vault(&mut rng, &mut map, &data, &[]);
+        let (key, loot): (Vec<_>, Vec<_>) = items.iter().partition(|(_, i)| i.name == "Vault Key");
+        assert_eq!(key.len(), 1);
+        assert!(!loot.is_empty());
+
+        // Everything but the vault is reachable, and the vault opens up with the door
+        assert!(!check_map_connectivity(&map, start));
+        let door = map
+            .tiles()
+            .into_iter()
+            .find(|p| map.get(*p).kind == TileKind::LockedDoor)
+            .unwrap();
+        map.set(door, MapTile::door(true));
+        assert!(check_map_connectivity(&map, start));
+    }
+}
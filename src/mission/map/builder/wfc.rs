@@ -0,0 +1,408 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use macroquad::rand::ChooseRandom;
+
+use crate::mission::*;
+use crate::prelude::*;
+
+const WFC_SAMPLES: [&str; 3] = [
+    include_str!("../../../../data/wfc/rooms.txt"),
+    include_str!("../../../../data/wfc/caves.txt"),
+    include_str!("../../../../data/wfc/maze.txt"),
+];
+
+// Right, left, down, up
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+pub struct WfcMapBuilder;
+
+impl InitialMapBuilder for WfcMapBuilder {
+    fn build_map(&mut self, rng: &mut RandGenerator, build: &mut BuilderMap) {
+        let sample = WFC_SAMPLES
+            .choose_with_state(rng)
+            .expect("At least one sample");
+        // Some seeds keep contradicting themselves or leave too little cave, so fall back to cells
+        if !build_from_sample(sample, rng, build) {
+            CellsMapBuilder.build_map(rng, build);
+        }
+    }
+}
+
+// False if no attempt gave a large enough connected map
+fn build_from_sample(sample: &str, rng: &mut RandGenerator, build: &mut BuilderMap) -> bool {
+    let patterns = WfcPatterns::learn(sample);
+
+    for _ in 0..WFC_MAX_ATTEMPTS {
+        build.map = Map::new_filled(build.map.theme);
+        let Some(floors) = patterns.collapse(rng, SCREEN_WIDTH, SCREEN_HEIGHT) else {
+            continue;
+        };
+        for (i, floor) in floors.into_iter().enumerate() {
+            let position = Point::new(i as i32 % SCREEN_WIDTH, i as i32 / SCREEN_WIDTH);
+            let tile = if floor {
+                MapTile::floor(rng)
+            } else {
+                MapTile::wall(rng)
+            };
+            build.map.set(position, tile);
+        }
+        fix_map_border(&mut build.map, rng);
+        build.take_snapshot();
+
+        // Culling only takes floor away, and a map without any has no center
+        if find_all_floors(&build.map).len() < WFC_MIN_FLOOR_AMOUNT as usize {
+            continue;
+        }
+
+        // Collapsing only looks at neighbors, so pockets cut off from the rest are common
+        let center = find_map_center(&build.map);
+        cull_unreachable(&mut build.map, center, rng);
+        build.take_snapshot();
+        if find_all_floors(&build.map).len() >= WFC_MIN_FLOOR_AMOUNT as usize
+            && check_map_connectivity(&build.map, center)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+// Every square of WFC_PATTERN_SIZE tiles seen in a sample (rotated and mirrored),
+// how often it was seen and which patterns may overlap it one tile over
+pub struct WfcPatterns {
+    tiles: Vec<Vec<bool>>,
+    weights: Vec<u32>,
+    // Per direction and pattern, the patterns allowed next to it
+    compatible: [Vec<Vec<usize>>; 4],
+}
+
+impl WfcPatterns {
+    // Samples are rows of '#' for walls and '.' for floors
+    pub fn learn(sample: &str) -> Self {
+        let grid = sample
+            .lines()
+            .map(|l| l.trim_end())
+            .filter(|l| !l.is_empty())
+            .map(|l| l.chars().map(|c| c == '.').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut variants = vec![grid];
+        for _ in 0..3 {
+            variants.push(rotate(variants.last().expect("Has a variant")));
+        }
+        for i in 0..4 {
+            let mirrored = variants[i]
+                .iter()
+                .map(|row| row.iter().rev().copied().collect())
+                .collect();
+            variants.push(mirrored);
+        }
+
+        let size = WFC_PATTERN_SIZE;
+        let mut tiles = vec![];
+        let mut weights = vec![];
+        let mut index = HashMap::new();
+        for variant in &variants {
+            for y in 0..=variant.len().saturating_sub(size) {
+                for x in 0..=variant[y].len().saturating_sub(size) {
+                    let pattern = (0..size * size)
+                        .map(|i| {
+                            variant
+                                .get(y + i / size)
+                                .and_then(|row| row.get(x + i % size))
+                                .copied()
+                                .unwrap_or(false)
+                        })
+                        .collect::<Vec<_>>();
+                    match index.get(&pattern) {
+                        Some(i) => weights[*i] += 1,
+                        None => {
+                            index.insert(pattern.clone(), tiles.len());
+                            tiles.push(pattern);
+                            weights.push(1);
+                        }
+                    }
+                }
+            }
+        }
+
+        let compatible = DIRECTIONS.map(|(dx, dy)| {
+            tiles
+                .iter()
+                .map(|a| {
+                    (0..tiles.len())
+                        .filter(|b| overlaps(a, &tiles[*b], dx, dy))
+                        .collect()
+                })
+                .collect()
+        });
+
+        Self {
+            tiles,
+            weights,
+            compatible,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    // Returns if each tile (row by row) is floor, or None if the rules contradicted themselves
+    pub fn collapse(&self, rng: &mut RandGenerator, width: i32, height: i32) -> Option<Vec<bool>> {
+        let size = WFC_PATTERN_SIZE as i32;
+        let patterns = self.count();
+        let grid_width = (width - size + 1).max(1);
+        let grid_height = (height - size + 1).max(1);
+        let cells = (grid_width * grid_height) as usize;
+
+        let mut wave = Wave {
+            possible: vec![true; cells * patterns],
+            counts: vec![patterns as u32; cells],
+            // Every pattern starts supported by all of its compatible neighbors
+            support: (0..cells)
+                .flat_map(|_| {
+                    (0..patterns).flat_map(|p| (0..4).map(move |d| self.compatible[d][p].len()))
+                })
+                .map(|s| s as u32)
+                .collect(),
+            banned: vec![],
+            touched: vec![false; cells],
+            touched_cells: vec![],
+            patterns,
+            width: grid_width,
+            height: grid_height,
+            // Breaks ties between cells with the same number of options
+            noise: (0..cells).map(|_| rng.gen_range(0, 1000)).collect(),
+            lowest: BinaryHeap::new(),
+        };
+        for cell in 0..cells {
+            wave.push_entropy(cell);
+        }
+
+        while let Some(Reverse((entropy, cell))) = wave.lowest.pop() {
+            // Stale entries are left behind whenever a cell loses options
+            if wave.counts[cell] <= 1 || entropy != wave.entropy(cell) {
+                continue;
+            }
+
+            let options = wave.options(cell);
+            let total = options.iter().map(|p| self.weights[*p]).sum::<u32>();
+            let mut roll = rng.gen_range(0, total);
+            let chosen = *options
+                .iter()
+                .find(|p| {
+                    if roll < self.weights[**p] {
+                        true
+                    } else {
+                        roll -= self.weights[**p];
+                        false
+                    }
+                })
+                .expect("Roll is within the total");
+
+            for pattern in options.into_iter().filter(|p| *p != chosen) {
+                if !wave.ban(cell, pattern) {
+                    return None;
+                }
+            }
+            if !self.propagate(&mut wave) {
+                return None;
+            }
+            wave.push_touched();
+        }
+
+        let mut floors = vec![false; (width * height) as usize];
+        for y in 0..height {
+            for x in 0..width {
+                let cell_x = x.min(grid_width - 1);
+                let cell_y = y.min(grid_height - 1);
+                let pattern = wave.options((cell_y * grid_width + cell_x) as usize)[0];
+                let offset = ((y - cell_y) * size + (x - cell_x)) as usize;
+                floors[(y * width + x) as usize] = self.tiles[pattern][offset];
+            }
+        }
+        Some(floors)
+    }
+
+    // A pattern next to a banned one loses that support, and is banned itself once it has none
+    fn propagate(&self, wave: &mut Wave) -> bool {
+        while let Some((cell, pattern)) = wave.banned.pop() {
+            let x = cell as i32 % wave.width;
+            let y = cell as i32 / wave.width;
+
+            for (direction, (dx, dy)) in DIRECTIONS.iter().enumerate() {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= wave.width || ny >= wave.height {
+                    continue;
+                }
+                let neighbor = (ny * wave.width + nx) as usize;
+
+                // Directions come in pairs, so flipping the low bit points back at us
+                let back = direction ^ 1;
+                for other in &self.compatible[direction][pattern] {
+                    let support = &mut wave.support[(neighbor * wave.patterns + other) * 4 + back];
+                    *support -= 1;
+                    if *support == 0
+                        && wave.possible[neighbor * wave.patterns + other]
+                        && !wave.ban(neighbor, *other)
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+// The patterns each output cell could still be
+struct Wave {
+    possible: Vec<bool>,
+    counts: Vec<u32>,
+    // How many patterns one step in each direction still allow a cell's pattern
+    support: Vec<u32>,
+    banned: Vec<(usize, usize)>,
+    // Cells that lost options since they were last pushed onto the heap
+    touched: Vec<bool>,
+    touched_cells: Vec<usize>,
+    patterns: usize,
+    width: i32,
+    height: i32,
+    noise: Vec<u32>,
+    // Cells by how few options they have left, to pick the next one to collapse
+    lowest: BinaryHeap<Reverse<(u32, usize)>>,
+}
+
+impl Wave {
+    fn entropy(&self, cell: usize) -> u32 {
+        self.counts[cell] * 1000 + self.noise[cell]
+    }
+
+    fn push_entropy(&mut self, cell: usize) {
+        self.lowest.push(Reverse((self.entropy(cell), cell)));
+    }
+
+    fn push_touched(&mut self) {
+        while let Some(cell) = self.touched_cells.pop() {
+            self.touched[cell] = false;
+            self.push_entropy(cell);
+        }
+    }
+
+    fn options(&self, cell: usize) -> Vec<usize> {
+        (0..self.patterns)
+            .filter(|p| self.possible[cell * self.patterns + p])
+            .collect()
+    }
+
+    // False when the cell has nothing left it could be
+    fn ban(&mut self, cell: usize, pattern: usize) -> bool {
+        self.possible[cell * self.patterns + pattern] = false;
+        self.counts[cell] -= 1;
+        self.banned.push((cell, pattern));
+        if !self.touched[cell] {
+            self.touched[cell] = true;
+            self.touched_cells.push(cell);
+        }
+        self.counts[cell] > 0
+    }
+}
+
+fn rotate(grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let height = grid.len();
+    let width = grid.iter().map(|r| r.len()).max().unwrap_or(0);
+    (0..width)
+        .map(|x| {
+            (0..height)
+                .map(|y| grid[height - 1 - y].get(x).copied().unwrap_or(false))
+                .collect()
+        })
+        .collect()
+}
+
+// If b can sit one step of (dx, dy) away from a with the shared tiles matching
+fn overlaps(a: &[bool], b: &[bool], dx: i32, dy: i32) -> bool {
+    let size = WFC_PATTERN_SIZE as i32;
+    for y in 0..size {
+        for x in 0..size {
+            let (bx, by) = (x - dx, y - dy);
+            if bx >= 0
+                && by >= 0
+                && bx < size
+                && by < size
+                && a[(y * size + x) as usize] != b[(by * size + bx) as usize]
+            {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn wfc_follows_sample() {
+        let patterns = WfcPatterns::learn("#.#.#.\n#.#.#.\n#.#.#.\n#.#.#.\n");
+        // Stripes one way or the other, starting on either tile
+        assert_eq!(patterns.count(), 4);
+
+        let mut rng = RandGenerator::new();
+        rng.srand(1);
+        let floors = patterns.collapse(&mut rng, 12, 10).unwrap();
+        let tile = |x: i32, y: i32| floors[(y * 12 + x) as usize];
+        let vertical = (0..12).all(|x| (0..10).all(|y| tile(x, y) == tile(x, 0)));
+        let horizontal = (0..10).all(|y| (0..12).all(|x| tile(x, y) == tile(0, y)));
+        assert!(vertical || horizontal);
+    }
+
+    #[test]
+    fn wfc_map_connected() {
+        let player = create_test_player();
+
+        for seed in 0..3 {
+            let mut rng = RandGenerator::new();
+            rng.srand(seed);
+
+            let level = BuilderChain::new(1, WfcMapBuilder)
+                .with_cave_start()
+                .with_level_features()
+                .build(&mut rng)
+                .into_level(player.clone());
+            assert!(level.items.iter().any(|(_, i)| i.name == "Runestone"));
+            assert_level_connected(&level);
+        }
+    }
+
+    #[test]
+    fn every_sample_builds_within_the_attempts() {
+        for sample in super::WFC_SAMPLES {
+            for seed in 0..4 {
+                let mut rng = RandGenerator::new();
+                rng.srand(seed);
+                let mut build = BuilderMap::new(Map::new_filled(MapTheme::Stone), 1);
+                assert!(super::build_from_sample(sample, &mut rng, &mut build));
+                let center = find_map_center(&build.map);
+                assert!(check_map_connectivity(&build.map, center));
+            }
+        }
+    }
+
+    #[test]
+    fn hopeless_sample_gives_up() {
+        // Never has enough floor, so every attempt fails
+        let mut rng = RandGenerator::new();
+        rng.srand(1);
+        let mut build = BuilderMap::new(Map::new_filled(MapTheme::Stone), 1);
+        assert!(!super::build_from_sample(
+            "###\n###\n###\n",
+            &mut rng,
+            &mut build
+        ));
+    }
+}
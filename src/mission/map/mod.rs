@@ -41,64 +41,38 @@ impl MapTheme {
 
 impl MapTheme {
     pub fn get_sprite(&self, tile: MapTile) -> Point {
-        let mut sprite = match self {
-            MapTheme::Stone => match tile.kind {
-                TileKind::Wall => Point::new(1, 1),
-                TileKind::Floor => Point::new(4, 1),
-                TileKind::Exit => Point::new(8, 1),
-            },
-            MapTheme::BrownStone => match tile.kind {
-                TileKind::Wall => Point::new(1, 3),
-                TileKind::Floor => Point::new(4, 3),
-                TileKind::Exit => Point::new(8, 3),
-            },
-            MapTheme::Sand => match tile.kind {
-                TileKind::Wall => Point::new(1, 9),
-                TileKind::Floor => Point::new(4, 9),
-                TileKind::Exit => Point::new(8, 9),
-            },
-            MapTheme::GreenMetal => match tile.kind {
-                TileKind::Wall => Point::new(1, 6),
-                TileKind::Floor => Point::new(4, 6),
-                TileKind::Exit => Point::new(8, 6),
-            },
-            MapTheme::BlueMetal => match tile.kind {
-                TileKind::Wall => Point::new(1, 7),
-                TileKind::Floor => Point::new(4, 7),
-                TileKind::Exit => Point::new(8, 7),
-            },
-            MapTheme::GreyMetal => match tile.kind {
-                TileKind::Wall => Point::new(1, 8),
-                TileKind::Floor => Point::new(4, 8),
-                TileKind::Exit => Point::new(8, 8),
-            },
-            MapTheme::Rivets => match tile.kind {
-                TileKind::Wall => Point::new(1, 11),
-                TileKind::Floor => Point::new(4, 11),
-                TileKind::Exit => Point::new(8, 11),
-            },
-            MapTheme::Hedge => match tile.kind {
-                TileKind::Wall => Point::new(1, 15),
-                TileKind::Floor => Point::new(4, 15),
-                TileKind::Exit => Point::new(8, 15),
-            },
-            MapTheme::Bones => match tile.kind {
-                TileKind::Wall => Point::new(1, 16),
-                TileKind::Floor => Point::new(4, 16),
-                TileKind::Exit => Point::new(8, 16),
-            },
-            MapTheme::Clay => match tile.kind {
-                TileKind::Wall => Point::new(1, 14),
-                TileKind::Floor => Point::new(4, 14),
-                TileKind::Exit => Point::new(8, 14),
-            },
+        let column = match tile.kind {
+            TileKind::Wall => 1,
+            TileKind::Floor => 4,
+            TileKind::Exit => 8,
+            TileKind::StairsDown => 9,
+            TileKind::Door { open: false } => 10,
+            TileKind::Door { open: true } => 11,
+            TileKind::LockedDoor => 12,
         };
+        let mut sprite = Point::new(column, self.sprite_row());
         if !matches!(tile.kind, TileKind::Floor) || self.supports_floor_variations() {
             sprite.x += tile.variation;
         }
         sprite
     }
 
+    // Each theme is a row in the world sheet
+    fn sprite_row(&self) -> i32 {
+        match self {
+            MapTheme::Stone => 1,
+            MapTheme::BrownStone => 3,
+            MapTheme::Sand => 9,
+            MapTheme::GreenMetal => 6,
+            MapTheme::BlueMetal => 7,
+            MapTheme::GreyMetal => 8,
+            MapTheme::Rivets => 11,
+            MapTheme::Hedge => 15,
+            MapTheme::Bones => 16,
+            MapTheme::Clay => 14,
+        }
+    }
+
     fn supports_floor_variations(&self) -> bool {
         match self {
             MapTheme::GreenMetal => false,
@@ -111,7 +85,13 @@ impl MapTheme {
 pub enum TileKind {
     Wall,
     Floor,
+    // Stairs up, which leave the mission from the top floor
     Exit,
+    StairsDown,
+    // Walking into a closed door opens it
+    Door { open: bool },
+    // Opened by walking into it carrying a vault key
+    LockedDoor,
 }
 
 #[derive(Debug, Serialize, Clone, Copy, Deserialize)]
@@ -148,10 +128,28 @@ impl MapTile {
         }
     }
 
+    pub fn door(open: bool) -> Self {
+        MapTile {
+            kind: TileKind::Door { open },
+            known: false,
+            variation: 0,
+        }
+    }
+
     pub fn can_enter(&self) -> bool {
         match self.kind {
-            TileKind::Wall => false,
-            TileKind::Floor | TileKind::Exit => true,
+            TileKind::Wall | TileKind::LockedDoor => false,
+            TileKind::Floor | TileKind::Exit | TileKind::StairsDown | TileKind::Door { .. } => true,
+        }
+    }
+
+    pub fn blocks_vision(&self) -> bool {
+        match self.kind {
+            TileKind::Wall | TileKind::LockedDoor | TileKind::Door { open: false } => true,
+            TileKind::Floor
+            | TileKind::Exit
+            | TileKind::StairsDown
+            | TileKind::Door { open: true } => false,
         }
     }
 }
@@ -253,7 +251,7 @@ impl Map {
             [SCREEN_WIDTH, SCREEN_HEIGHT],
             |p| {
                 let p = Point::new(p.x, p.y);
-                self.in_bounds(p) && self.get(p).kind == TileKind::Wall
+                self.in_bounds(p) && self.get(p).blocks_vision()
             },
             |p| {
                 visibility.set_visible(Point::new(p.x, p.y));
@@ -270,6 +268,9 @@ impl Map {
                     TileKind::Floor => print!("."),
                     TileKind::Wall => print!("#"),
                     TileKind::Exit => print!("<"),
+                    TileKind::StairsDown => print!(">"),
+                    TileKind::Door { .. } => print!("+"),
+                    TileKind::LockedDoor => print!("="),
                 }
             }
             println!("");
@@ -313,4 +314,17 @@ mod tests {
         assert!(!v.get(Point::new(5, 14)));
         assert!(!v.get(Point::new(5, 15)));
     }
+
+    #[test]
+    fn closed_doors_block_visibility() {
+        let mut map = Map::new(MapTheme::Stone);
+        map.set(Point::new(5, 7), MapTile::door(false));
+        let v = map.compute_visibility(Point::new(5, 5));
+        assert!(v.get(Point::new(5, 7)));
+        assert!(!v.get(Point::new(5, 9)));
+
+        map.set(Point::new(5, 7), MapTile::door(true));
+        let v = map.compute_visibility(Point::new(5, 5));
+        assert!(v.get(Point::new(5, 9)));
+    }
 }
@@ -1,16 +1,20 @@
 mod actor;
 mod ai;
 mod animation;
+mod autopilot;
 mod camera;
 mod character;
 mod damage;
 mod data;
 mod engine;
 mod health;
+mod inventory;
 mod items;
 mod map;
 mod path;
 mod player;
+mod replay;
+mod save;
 mod skills;
 mod state;
 mod targeting;
@@ -19,16 +23,20 @@ mod util;
 pub use actor::*;
 pub use ai::*;
 pub use animation::*;
+pub use autopilot::*;
 pub use camera::*;
 pub use character::*;
 pub use damage::*;
 pub use data::*;
 pub use engine::*;
 pub use health::*;
+pub use inventory::*;
 pub use items::*;
 pub use map::*;
 pub use path::*;
 pub use player::*;
+pub use replay::*;
+pub use save::*;
 pub use skills::*;
 pub use state::*;
 pub use targeting::*;
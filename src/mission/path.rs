@@ -28,13 +28,13 @@ pub fn clear_line_between(
 }
 
 pub fn can_see(level: &LevelState, first: CharacterId, second: CharacterId) -> bool {
-    let first = level.find_character(first).position;
-    let second = level.find_character(second).position;
-    level.map.compute_visibility(first).get(second)
-}
-
-pub fn can_see_player(level: &LevelState, id: CharacterId) -> bool {
-    can_see(level, id, level.get_player().id)
+    match (level.find_character(first), level.find_character(second)) {
+        (Some(first), Some(second)) => level
+            .map
+            .compute_visibility(first.position)
+            .get(second.position),
+        _ => false,
+    }
 }
 
 pub fn path_distance_between(
@@ -52,18 +52,19 @@ pub fn path_distance_between(
 }
 
 pub fn distance_to_player(level: &LevelState, id: CharacterId) -> Option<usize> {
+    let character = level.find_character(id)?;
     path_distance_between(
         level,
-        level.find_character(id).position,
-        level.get_player().position,
-        PathCharacterOptions::AllowEmptyOrPlayer,
+        character.position,
+        level.get_player()?.position,
+        PathCharacterOptions::AllowEmptyOrHostile(character.faction),
     )
 }
 
 #[derive(Debug, Copy, Clone)]
 pub enum PathCharacterOptions {
-    AllowEmptyOrPlayer,
-    AllowEmptyOrEnemies,
+    // Characters not on the given side, who can be attacked out of the way
+    AllowEmptyOrHostile(Faction),
     All,
     AllCharactersBlock,
 }
@@ -93,8 +94,7 @@ pub fn adjacent_squares(
             let can_enter = level.character_can_enter(*p);
             let acceptable_occupants = match level.find_character_at_position(*p) {
                 Some(c) => match options {
-                    PathCharacterOptions::AllowEmptyOrPlayer => c.is_player(),
-                    PathCharacterOptions::AllowEmptyOrEnemies => !c.is_player(),
+                    PathCharacterOptions::AllowEmptyOrHostile(faction) => c.faction != faction,
                     PathCharacterOptions::All => true,
                     PathCharacterOptions::AllCharactersBlock => false,
                 },
@@ -3,17 +3,17 @@ use macroquad::input::is_key_down;
 use crate::mission::*;
 use crate::prelude::*;
 
-pub fn get_player_action(
+pub fn get_player_action<S: ScreenInterface>(
     player: &Character,
     level: &LevelState,
-    screen: &mut Screen,
+    screen: &mut S,
 ) -> HandleInputResponse {
-    if screen.is_player_resting {
+    if screen.is_player_resting() {
         if is_hostile_near_player(level)
             || (player.health.current == player.health.max
                 && player.will.current == player.will.max)
         {
-            screen.is_player_resting = false;
+            screen.set_player_resting(false);
         } else {
             return HandleInputResponse::Action(Some(RequestedAction::Wait(player.id)));
         }
@@ -25,16 +25,18 @@ pub fn get_player_action(
             player.position + movement_delta,
             level,
         )))
+    } else if (is_key_pressed(KeyCode::Comma) || is_key_pressed(KeyCode::Period))
+        && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift))
+    {
+        HandleInputResponse::Action(Some(RequestedAction::Stairs))
     } else if is_key_pressed(KeyCode::Period) || is_key_pressed(KeyCode::Kp5) {
         HandleInputResponse::Action(Some(RequestedAction::Wait(player.id)))
+    } else if is_key_pressed(KeyCode::I) {
+        HandleInputResponse::ChangeActor(CurrentActor::PlayerInventory(InventoryInfo::new()))
     } else if is_key_pressed(KeyCode::R) {
-        screen.is_player_resting = true;
+        screen.set_player_resting(true);
         screen.push_floating_text("Resting");
         HandleInputResponse::Action(Some(RequestedAction::Wait(player.id)))
-    } else if is_key_pressed(KeyCode::Comma)
-        && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift))
-    {
-        HandleInputResponse::Action(Some(RequestedAction::Stairs))
     } else if let Some(index) = skill_index_from_number_key_pressed() {
         if let Some(skill) = player.skills.get(index) {
             if skill.cost.can_pay(player) {
@@ -46,14 +48,23 @@ pub fn get_player_action(
                             skill_name: skill.name.clone(),
                         }))
                     }
-                    SkillTargeting::Ranged { max_range, sprite } => {
+                    SkillTargeting::PointBlank { .. } => skill.targeting.area_skill_response(
+                        level,
+                        player,
+                        player.position,
+                        &skill.name,
+                    ),
+                    SkillTargeting::Ranged { max_range, .. }
+                    | SkillTargeting::Blast { max_range, .. }
+                    | SkillTargeting::Line { max_range, .. }
+                    | SkillTargeting::Cone { length: max_range } => {
                         screen.push_floating_text(&format!("Targeting {}", skill.name));
 
                         HandleInputResponse::ChangeActor(CurrentActor::PlayerTargeting(
                             TargetingInfo::new(
                                 player.position,
                                 TargetSkill {
-                                    spite: sprite.clone(),
+                                    targeting: skill.targeting.clone(),
                                     skill_name: skill.name.clone(),
                                 },
                                 *max_range,
@@ -0,0 +1,185 @@
+use std::path::Path;
+
+use crate::mission::*;
+use crate::prelude::*;
+use crate::storage;
+
+// A replay is the mission as it started (which includes the seeded rng)
+// plus every action resolved during it, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: String,
+    pub seed: u64,
+    pub start: Box<MissionState>,
+    pub actions: Vec<RequestedAction>,
+}
+
+impl Replay {
+    pub fn new(start: &MissionState) -> Self {
+        let mut start = start.clone();
+        start.replay = None;
+
+        Self {
+            version: VERSION.to_string(),
+            seed: start.seed(),
+            start: Box::new(start),
+            actions: vec![],
+        }
+    }
+
+    pub fn record(&mut self, action: &RequestedAction) {
+        self.actions.push(action.clone());
+    }
+
+    // Replays are too large to keep in the browser's localStorage
+    #[cfg(feature = "desktop")]
+    pub fn save_to_disk(&self) {
+        storage::write(
+            &format!("replays/{}.replay", self.seed),
+            &serde_json::to_string(self).expect("Unable to save replay"),
+        );
+    }
+
+    #[cfg(not(feature = "desktop"))]
+    pub fn save_to_disk(&self) {}
+
+    #[cfg(feature = "desktop")]
+    pub fn save_to_slot(&self, slot: usize) {
+        storage::write(
+            &replay_slot_key(slot),
+            &serde_json::to_string(self).expect("Unable to save replay"),
+        );
+    }
+
+    #[cfg(not(feature = "desktop"))]
+    pub fn save_to_slot(&self, _slot: usize) {}
+
+    // Only trusted if it is for the same run, the slot may have been copied over since
+    pub fn load_from_slot(slot: usize, mission: &MissionState) -> Option<Self> {
+        let text = storage::read(&replay_slot_key(slot))?;
+        serde_json::from_str::<Self>(&text)
+            .ok()
+            .filter(|replay| replay.seed == mission.seed())
+    }
+
+    pub fn load_from_disk(filename: &Path) -> Option<Self> {
+        match std::fs::read(filename) {
+            Ok(text) => match serde_json::from_slice(&text) {
+                Ok(replay) => Some(replay),
+                Err(e) => {
+                    eprintln!("Unable to load replay: {e:?}");
+                    None
+                }
+            },
+            Err(e) => {
+                eprintln!("Unable to read replay: {e:?}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Difficulty;
+    use crate::campaign::{CampaignState, RuneKinds, load_equipment_options};
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    type Snapshot = Vec<(CharacterId, Point, i32)>;
+
+    fn snapshot(level: &LevelState) -> Snapshot {
+        level
+            .characters
+            .iter()
+            .map(|c| (c.id, c.position, c.health.current))
+            .collect()
+    }
+
+    // Plays the actions back the way the replay screen does, with None once they stop lining up
+    fn play_back(replay: &Replay) -> Option<(usize, Snapshot)> {
+        let mut mission = (*replay.start).clone();
+        let mut actions = replay.actions.iter();
+        let mut screen = EmptyScreen {};
+        while !mission.is_player_dead() && !mission.mission_complete {
+            match &mission.current_actor {
+                CurrentActor::PlayerStandardAction
+                | CurrentActor::PlayerTargeting(_)
+                | CurrentActor::PlayerInventory(_) => {
+                    let Some(action) = actions.next() else {
+                        break;
+                    };
+                    mission.current_actor = CurrentActor::PlayerStandardAction;
+                    mission.process_action(action.clone(), &mut screen);
+                }
+                CurrentActor::EnemyAction(_) | CurrentActor::Animation(_) => {
+                    if let Some(action) = mission.current_actor.act(
+                        &mut mission.level,
+                        mission.controller,
+                        &mut screen,
+                    ) {
+                        if actions.next() != Some(&action) {
+                            return None;
+                        }
+                        mission.process_action(action, &mut screen);
+                    }
+                }
+            }
+        }
+        Some((mission.depth, snapshot(&mission.level)))
+    }
+
+    #[test]
+    fn playback_matches_after_the_id_counter_moves_on() {
+        let player = load_equipment_options()[0].outfit_character(Difficulty::Normal);
+        // A rune in hand allows a second floor, which this seed has
+        let mut campaign = CampaignState::new(player);
+        campaign.collected_runes.insert(RuneKinds::Ice);
+        let mut state = MissionState::new_with_seed(campaign, RuneKinds::Fire, 3);
+        assert_eq!(state.floors.len(), 2);
+        state.controller = PlayerController::Autopilot;
+        state.replay = Some(Replay::new(&state));
+
+        let mut screen = EmptyScreen {};
+        let mut deepest = 0;
+        for _ in 0..5000 {
+            let finished = state.process_turns(&mut screen).is_some();
+            deepest = deepest.max(state.depth);
+            if finished {
+                break;
+            }
+        }
+        // The new floor is where ids get handed out mid mission
+        assert_eq!(deepest, 1);
+
+        // As if the replay were watched in a later run of the game
+        for _ in 0..100 {
+            CharacterId::next();
+        }
+        let replay = state.replay.as_ref().unwrap();
+        assert_eq!(
+            play_back(replay),
+            Some((state.depth, snapshot(&state.level)))
+        );
+    }
+
+    #[test]
+    fn records_start_and_actions() {
+        let data = Data::load().unwrap();
+        let mut player = data.get_character("Bat");
+        player.faction = Faction::Player;
+
+        let mut state = MissionState::new_with_seed(CampaignState::new(player), RuneKinds::Fire, 5);
+        let player_id = state.level.get_player().unwrap().id;
+
+        let replay = state.replay.as_mut().unwrap();
+        replay.record(&RequestedAction::Wait(player_id));
+
+        let text = serde_json::to_string(replay).unwrap();
+        let loaded: Replay = serde_json::from_str(&text).unwrap();
+        assert_eq!(loaded.seed, 5);
+        assert_eq!(loaded.start.seed(), 5);
+        assert!(loaded.start.replay.is_none());
+        assert_eq!(loaded.actions, vec![RequestedAction::Wait(player_id)]);
+    }
+}
@@ -0,0 +1,607 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::campaign::{CampaignScreenState, CampaignState, CraftingGrid, RuneKinds};
+use crate::mission::*;
+use crate::prelude::*;
+use crate::storage;
+
+pub const SAVE_SLOT_COUNT: usize = 5;
+
+// Bump whenever a change would stop older saves from deserializing,
+// and add a migration from the previous format to MIGRATIONS
+pub const SAVE_FORMAT: u32 = 8;
+
+// Entry N upgrades a save from format N to N + 1
+const MIGRATIONS: [fn(Value) -> Result<Value, String>; SAVE_FORMAT as usize] = [
+    wrap_bare_mission,
+    add_envelope_version,
+    tag_saved_mission,
+    targeting_skill_targeting,
+    player_faction,
+    level_player_id,
+    upgrade_crafting_grid,
+    potion_items,
+];
+
+// What the slot browser shows without needing to load the whole mission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub version: String,
+    pub kit: String,
+    pub collected_runes: Vec<RuneKinds>,
+    pub mission_count: u32,
+    // In seconds
+    pub playtime: f64,
+}
+
+impl SaveMetadata {
+    pub fn new(campaign: &CampaignState) -> Self {
+        Self {
+            version: VERSION.to_string(),
+            kit: campaign.kit.clone(),
+            collected_runes: RuneKinds::all()
+                .into_iter()
+                .filter(|r| campaign.collected_runes.contains(r))
+                .collect(),
+            mission_count: campaign.completed_mission_count() + 1,
+            playtime: campaign.playtime,
+        }
+    }
+
+    pub fn playtime_text(&self) -> String {
+        let minutes = (self.playtime / 60.0) as u64;
+        format!("{}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+// Runs can be saved in the middle of a mission or between them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SavedGame {
+    Mission(Box<MissionState>),
+    Campaign(Box<CampaignScreenState>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveFile {
+    pub format: u32,
+    // Game version that wrote the save
+    pub version: String,
+    pub metadata: SaveMetadata,
+    pub state: SavedGame,
+}
+
+impl SaveFile {
+    pub fn new(campaign: &CampaignState, state: SavedGame) -> Self {
+        Self {
+            format: SAVE_FORMAT,
+            version: VERSION.to_string(),
+            metadata: SaveMetadata::new(campaign),
+            state,
+        }
+    }
+}
+
+// Reading just the metadata skips building the level
+#[derive(Deserialize)]
+struct SaveFileHeader {
+    metadata: SaveMetadata,
+}
+
+// Format 0 is a bare MissionState (the old game.sav), format 1 added
+// the metadata wrapper and from 2 on the format is written in the file
+fn save_format(save: &Value) -> u64 {
+    match save.get("format") {
+        Some(format) => format.as_u64().unwrap_or(u64::MAX),
+        None if save.get("metadata").is_some() && save.get("state").is_some() => 1,
+        None => 0,
+    }
+}
+
+pub fn upgrade_save(mut save: Value) -> Result<Value, String> {
+    let mut format = save_format(&save);
+    if format > SAVE_FORMAT as u64 {
+        return Err(format!(
+            "This save is from a newer version of the game ({}).",
+            save["version"].as_str().unwrap_or("unknown")
+        ));
+    }
+
+    while format < SAVE_FORMAT as u64 {
+        save = MIGRATIONS[format as usize](save)
+            .map_err(|e| format!("Unable to upgrade save from format {format}: {e}"))?;
+        format += 1;
+    }
+    Ok(save)
+}
+
+fn wrap_bare_mission(state: Value) -> Result<Value, String> {
+    let collected_runes = state
+        .pointer("/campaign/collected_runes")
+        .and_then(|r| r.as_array())
+        .ok_or("missing collected runes")?
+        .clone();
+
+    Ok(json!({
+        "metadata": {
+            "version": "unknown",
+            "kit": "Unknown",
+            "mission_count": collected_runes.len() + 1,
+            "collected_runes": collected_runes,
+            "playtime": 0.0,
+        },
+        "state": state,
+    }))
+}
+
+fn add_envelope_version(mut save: Value) -> Result<Value, String> {
+    let version = save
+        .pointer("/metadata/version")
+        .cloned()
+        .ok_or("missing metadata version")?;
+
+    let envelope = save.as_object_mut().ok_or("save is not an object")?;
+    envelope.insert("format".to_string(), json!(2));
+    envelope.insert("version".to_string(), version);
+    Ok(save)
+}
+
+fn tag_saved_mission(mut save: Value) -> Result<Value, String> {
+    let envelope = save.as_object_mut().ok_or("save is not an object")?;
+    let state = envelope.remove("state").ok_or("missing state")?;
+    envelope.insert("state".to_string(), json!({ "Mission": state }));
+    envelope.insert("format".to_string(), json!(3));
+    Ok(save)
+}
+
+// Player targeting now carries the skill's full targeting instead of just its sprite
+fn targeting_skill_targeting(mut save: Value) -> Result<Value, String> {
+    if let Some(targeting) = save.pointer_mut("/state/Mission/current_actor/PlayerTargeting") {
+        let max_range = targeting["max_range"].clone();
+        let target_skill = targeting
+            .get_mut("target_skill")
+            .and_then(|t| t.as_object_mut())
+            .ok_or("missing target skill")?;
+        let sprite = target_skill
+            .remove("spite")
+            .ok_or("missing target sprite")?;
+        target_skill.insert(
+            "targeting".to_string(),
+            json!({ "Ranged": { "max_range": max_range, "sprite": sprite } }),
+        );
+    }
+
+    save["format"] = json!(4);
+    Ok(save)
+}
+
+// Characters default to the monsters' side, so the player (wherever they are saved) needs moving over
+fn player_faction(mut save: Value) -> Result<Value, String> {
+    fn visit(value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                if object.contains_key("base_sprite_tile")
+                    && object.get("name").and_then(|n| n.as_str()) == Some("Player")
+                {
+                    object.insert("faction".to_string(), json!("Player"));
+                }
+                object.values_mut().for_each(visit);
+            }
+            Value::Array(values) => values.iter_mut().for_each(visit),
+            _ => {}
+        }
+    }
+
+    visit(&mut save);
+    save["format"] = json!(5);
+    Ok(save)
+}
+
+// Levels remember which character is the player rather than going by name
+fn level_player_id(mut save: Value) -> Result<Value, String> {
+    fn upgrade_mission(mission: &mut Value) -> Result<(), String> {
+        let player = mission
+            .pointer("/level/characters")
+            .and_then(|c| c.as_array())
+            .and_then(|c| c.iter().find(|c| c["name"] == "Player"))
+            .map(|c| c["id"].clone())
+            .ok_or("missing player")?;
+
+        mission["level"]["player"] = player.clone();
+        if let Some(floors) = mission.get_mut("floors").and_then(|f| f.as_array_mut()) {
+            for floor in floors.iter_mut().filter(|f| f.is_object()) {
+                floor["player"] = player.clone();
+            }
+        }
+        match mission.pointer_mut("/replay/start") {
+            Some(start) if start.is_object() => upgrade_mission(start),
+            _ => Ok(()),
+        }
+    }
+
+    if let Some(mission) = save.pointer_mut("/state/Mission") {
+        upgrade_mission(mission)?;
+    }
+    save["format"] = json!(6);
+    Ok(save)
+}
+
+// The upgrade screen went from three drawn options to a crafting grid. The options
+// for the rune found carry it as their tag, the last option was the generic one
+fn upgrade_crafting_grid(mut save: Value) -> Result<Value, String> {
+    if let Some(upgrade) = save.pointer_mut("/state/Campaign/step/SelectUpgrade") {
+        let options = upgrade
+            .as_object_mut()
+            .and_then(|u| u.remove("options"))
+            .ok_or("missing upgrade options")?;
+        let options = options.as_array().ok_or("upgrade options are not a list")?;
+        let rune_found = options
+            .iter()
+            .take(options.len().saturating_sub(1))
+            .find_map(|o| o["tags"].get(0).cloned())
+            .map(serde_json::from_value::<RuneKinds>)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+
+        // With nothing left for the rune found only the generic upgrades were offered,
+        // which is what an empty grid crafts
+        if let Some(rune_found) = rune_found {
+            upgrade["grid"] = json!(CraftingGrid::starting_with(rune_found));
+            upgrade["cursor"] = json!(CraftingGrid::center());
+        }
+        upgrade["selection"] = json!(0);
+    }
+
+    save["format"] = json!(7);
+    Ok(save)
+}
+
+// Potions were skills with charges and are carried items now, keeping what is left in them
+fn potion_items(mut save: Value) -> Result<Value, String> {
+    fn visit(value: &mut Value, data: &Data) {
+        match value {
+            Value::Object(object) => {
+                if object.contains_key("base_sprite_tile")
+                    && let Some(Value::Array(skills)) = object.get_mut("skills")
+                {
+                    let mut potions = vec![];
+                    skills.retain(|skill| {
+                        let name = skill["name"].as_str().unwrap_or_default();
+                        if !["Health Potion", "Rage Potion"].contains(&name) {
+                            return true;
+                        }
+                        let remaining = skill.pointer("/cost/Charges/remaining");
+                        if let Some(remaining) = remaining.and_then(|r| r.as_u64())
+                            && remaining > 0
+                        {
+                            let mut item = json!(data.get_item(name));
+                            item["charges"] = json!(remaining);
+                            potions.push(item);
+                        }
+                        false
+                    });
+                    if let Some(Value::Array(items)) = object.get_mut("carried_items") {
+                        items.append(&mut potions);
+                    }
+                }
+                object.values_mut().for_each(|v| visit(v, data));
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| visit(v, data)),
+            _ => {}
+        }
+    }
+
+    let data = Data::load().map_err(|e| e.to_string())?;
+    visit(&mut save, &data);
+    save["format"] = json!(8);
+    Ok(save)
+}
+
+fn parse_save<T: DeserializeOwned>(text: &str) -> Result<T, String> {
+    let save: Value =
+        serde_json::from_str(text).map_err(|e| format!("Save file is damaged: {e}"))?;
+    let save = upgrade_save(save)?;
+    let version = save["version"].as_str().unwrap_or("unknown").to_string();
+
+    serde_json::from_value(save)
+        .map_err(|e| format!("Save from version {version} can not be read: {e}"))
+}
+
+pub fn save_slot_key(slot: usize) -> String {
+    format!("saves/slot{}.sav", slot + 1)
+}
+
+pub fn replay_slot_key(slot: usize) -> String {
+    format!("saves/slot{}.replay", slot + 1)
+}
+
+// Saves from before slots existed are moved into the first free slot
+pub fn import_legacy_save() {
+    const LEGACY_KEY: &str = "game.sav";
+
+    let Some(text) = storage::read(LEGACY_KEY) else {
+        return;
+    };
+    if let Some(slot) = (0..SAVE_SLOT_COUNT).find(|slot| !storage::exists(&save_slot_key(*slot))) {
+        storage::write(&save_slot_key(slot), &text);
+        storage::remove(LEGACY_KEY);
+    }
+}
+
+// One entry per slot, None for empty slots and an error message for
+// saves that can not be upgraded to the current format
+pub fn load_save_slot_metadata() -> Vec<Option<Result<SaveMetadata, String>>> {
+    (0..SAVE_SLOT_COUNT)
+        .map(|slot| {
+            let text = storage::read(&save_slot_key(slot))?;
+            Some(parse_save::<SaveFileHeader>(&text).map(|header| header.metadata))
+        })
+        .collect()
+}
+
+pub fn any_save_slot_used() -> bool {
+    (0..SAVE_SLOT_COUNT).any(|slot| storage::exists(&save_slot_key(slot)))
+}
+
+pub fn write_save_slot(campaign: &CampaignState, state: SavedGame) {
+    if let SavedGame::Mission(mission) = &state
+        && let Some(replay) = &mission.replay
+    {
+        replay.save_to_slot(campaign.save_slot);
+    }
+
+    let save = SaveFile::new(campaign, state);
+    storage::write(
+        &save_slot_key(campaign.save_slot),
+        &serde_json::to_string(&save).expect("Unable to save game"),
+    );
+}
+
+pub fn load_save_slot(slot: usize) -> Result<SavedGame, String> {
+    let text = storage::read(&save_slot_key(slot))
+        .ok_or_else(|| format!("Unable to read save slot {}.", slot + 1))?;
+    let mut state = parse_save::<SaveFile>(&text)?.state;
+
+    // The file may have been copied from another slot
+    match &mut state {
+        SavedGame::Mission(mission) => {
+            mission.campaign.save_slot = slot;
+            // Without the earlier part the replay picks up from where the save was made
+            mission.replay =
+                Replay::load_from_slot(slot, mission).or_else(|| Some(Replay::new(mission)));
+        }
+        SavedGame::Campaign(campaign) => campaign.set_save_slot(slot),
+    }
+    if consumed_on_load(&state) {
+        delete_save_slot(slot);
+    }
+    Ok(state)
+}
+
+// A mission is taken out of its slot once loaded, like the single save before slots,
+// and only written back on quitting, so a lost fight can not be loaded again.
+// Between missions there is nothing to retry, the next mission plays out the same
+fn consumed_on_load(state: &SavedGame) -> bool {
+    matches!(state, SavedGame::Mission(_))
+}
+
+pub fn delete_save_slot(slot: usize) {
+    storage::remove(&save_slot_key(slot));
+    storage::remove(&replay_slot_key(slot));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::campaign::{CampaignScreenState, CampaignState, CraftingGrid, RunSetup, RuneKinds};
+    use crate::mission::*;
+
+    fn test_campaign() -> CampaignState {
+        let data = Data::load().unwrap();
+        let mut player = data.get_character("Bat");
+        player.name = "Player".to_string();
+        player.faction = Faction::Player;
+        CampaignState::new(player)
+    }
+
+    #[test]
+    fn metadata_readable_without_state() {
+        let mut campaign = test_campaign();
+        campaign.kit = "Sword".to_string();
+        campaign.playtime = 3725.0;
+        campaign.collected_runes.insert(RuneKinds::Ice);
+        campaign.collected_runes.insert(RuneKinds::Fire);
+
+        let save = SaveFile::new(
+            &campaign,
+            SavedGame::Campaign(Box::new(CampaignScreenState::new(0, RunSetup::custom(0)))),
+        );
+
+        let text = serde_json::to_string(&save).unwrap();
+        let header: super::SaveFileHeader = super::parse_save(&text).unwrap();
+        assert_eq!(header.metadata, save.metadata);
+        assert_eq!(header.metadata.kit, "Sword");
+        assert_eq!(
+            header.metadata.collected_runes,
+            vec![RuneKinds::Fire, RuneKinds::Ice]
+        );
+        assert_eq!(header.metadata.mission_count, 3);
+        assert_eq!(header.metadata.playtime_text(), "1:02");
+    }
+
+    #[test]
+    fn upgrades_bare_mission_save() {
+        let mut campaign = test_campaign();
+        campaign.collected_runes.insert(RuneKinds::Mind);
+        let state = MissionState::new_with_seed(campaign, RuneKinds::Life, 2);
+
+        // Saves from before factions have none stored, nor which character is the player
+        let mut value = serde_json::to_value(&state).unwrap();
+        for character in value["level"]["characters"].as_array_mut().unwrap() {
+            character.as_object_mut().unwrap().remove("faction");
+        }
+        value["level"].as_object_mut().unwrap().remove("player");
+        assert!(value.get("replay").is_none());
+
+        let text = serde_json::to_string(&value).unwrap();
+        let save: SaveFile = super::parse_save(&text).unwrap();
+        assert_eq!(save.format, SAVE_FORMAT);
+        assert_eq!(save.version, "unknown");
+        assert_eq!(save.metadata.collected_runes, vec![RuneKinds::Mind]);
+        assert_eq!(save.metadata.mission_count, 2);
+        let SavedGame::Mission(state) = save.state else {
+            panic!("Expected a mission save");
+        };
+        assert_eq!(state.seed(), 2);
+        assert_eq!(state.level.get_player().unwrap().faction, Faction::Player);
+        assert!(
+            state
+                .level
+                .characters
+                .iter()
+                .filter(|c| !state.level.is_player(c.id))
+                .all(|c| c.faction == Faction::Monsters)
+        );
+    }
+
+    #[test]
+    fn campaign_step_round_trips() {
+        let mut campaign = test_campaign();
+        campaign.collected_runes.insert(RuneKinds::Fire);
+        let screen = CampaignScreenState::mission_complete(campaign.clone(), RuneKinds::Ice);
+
+        let save = SaveFile::new(&campaign, SavedGame::Campaign(Box::new(screen.clone())));
+        let text = serde_json::to_string(&save).unwrap();
+        let loaded: SaveFile = super::parse_save(&text).unwrap();
+
+        let SavedGame::Campaign(loaded) = loaded.state else {
+            panic!("Expected a campaign save");
+        };
+        let campaign = loaded.campaign().unwrap();
+        assert_eq!(campaign.collected_runes.len(), 2);
+        assert!(campaign.collected_runes.contains(&RuneKinds::Ice));
+
+        let (upgrade, loaded) = (screen.upgrade().unwrap(), loaded.upgrade().unwrap());
+        assert_eq!(loaded.grid(), &CraftingGrid::starting_with(RuneKinds::Ice));
+        assert_eq!(loaded.grid(), upgrade.grid());
+        assert_eq!(loaded.cursor(), upgrade.cursor());
+        assert_eq!(loaded.craftable_count(), upgrade.craftable_count());
+        assert!(loaded.craftable_count() > 0);
+    }
+
+    #[test]
+    fn upgrades_drawn_options_to_crafting_grid() {
+        let mut campaign = test_campaign();
+        campaign.collected_runes.insert(RuneKinds::Fire);
+        let screen = CampaignScreenState::mission_complete(campaign.clone(), RuneKinds::Ice);
+        let save = SaveFile::new(&campaign, SavedGame::Campaign(Box::new(screen)));
+
+        // Two options for the rune found and a generic one, with no grid yet
+        let mut value = serde_json::to_value(&save).unwrap();
+        value["format"] = json!(6);
+        let upgrade = value
+            .pointer_mut("/state/Campaign/step/SelectUpgrade")
+            .unwrap()
+            .as_object_mut()
+            .unwrap();
+        upgrade.remove("grid");
+        upgrade.remove("cursor");
+        upgrade.insert(
+            "options".to_string(),
+            json!([{ "tags": ["Ice"] }, { "tags": ["Ice"] }, { "tags": ["Fire"] }]),
+        );
+
+        let text = serde_json::to_string(&value).unwrap();
+        let loaded: SaveFile = super::parse_save(&text).unwrap();
+        let SavedGame::Campaign(loaded) = loaded.state else {
+            panic!("Expected a campaign save");
+        };
+        let upgrade = loaded.upgrade().unwrap();
+        assert_eq!(upgrade.grid(), &CraftingGrid::starting_with(RuneKinds::Ice));
+        assert_eq!(upgrade.cursor(), CraftingGrid::center());
+    }
+
+    #[test]
+    fn upgrades_potion_skills_to_items() {
+        let data = Data::load().unwrap();
+        let campaign = test_campaign();
+        let state = MissionState::new_with_seed(campaign.clone(), RuneKinds::Life, 2);
+        let save = SaveFile::new(&campaign, SavedGame::Mission(Box::new(state)));
+
+        // One potion half drunk, a spare from Extra Health Potion and an empty one
+        let mut value = serde_json::to_value(&save).unwrap();
+        value["format"] = json!(7);
+        let mut potion = serde_json::to_value(data.get_skill("Firebolt")).unwrap();
+        potion["cost"] = json!({ "Charges": { "remaining": 4, "total": 7 } });
+        let potions = [
+            ("Health Potion", 4),
+            ("Health Potion", 7),
+            ("Rage Potion", 0),
+        ]
+        .map(|(name, remaining)| {
+            let mut potion = potion.clone();
+            potion["name"] = json!(name);
+            potion["cost"]["Charges"]["remaining"] = json!(remaining);
+            potion
+        });
+        let player = value
+            .pointer("/state/Mission/level/player")
+            .unwrap()
+            .clone();
+        let characters = value.pointer("/state/Mission/level/characters").unwrap();
+        let index = characters
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|c| c["id"] == player);
+        let player = format!("/state/Mission/level/characters/{}", index.unwrap());
+        for character in [player.as_str(), "/state/Mission/campaign/character"] {
+            let character = value.pointer_mut(character).unwrap();
+            character["carried_items"] = json!([]);
+            character["skills"]
+                .as_array_mut()
+                .unwrap()
+                .extend(potions.clone());
+        }
+
+        let text = serde_json::to_string(&value).unwrap();
+        let loaded: SaveFile = super::parse_save(&text).unwrap();
+        let SavedGame::Mission(state) = loaded.state else {
+            panic!("Expected a mission save");
+        };
+        for character in [state.level.get_player().unwrap(), &state.campaign.character] {
+            assert!(character.skills.iter().all(|s| !s.name.contains("Potion")));
+            let potions = character
+                .carried_items
+                .iter()
+                .map(|i| (i.name.as_str(), i.charges))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                potions,
+                vec![("Health Potion", Some(4)), ("Health Potion", Some(7))]
+            );
+        }
+    }
+
+    #[test]
+    fn newer_save_reports_error() {
+        let save = json!({ "format": SAVE_FORMAT + 1, "version": "9.99" });
+        let error = upgrade_save(save).unwrap_err();
+        assert!(error.contains("9.99"));
+    }
+
+    #[test]
+    fn only_missions_are_consumed_on_load() {
+        let campaign = test_campaign();
+        let mission = MissionState::new_with_seed(campaign.clone(), RuneKinds::Fire, 1);
+        assert!(super::consumed_on_load(&SavedGame::Mission(Box::new(
+            mission
+        ))));
+
+        let screen = CampaignScreenState::mission_complete(campaign, RuneKinds::Fire);
+        assert!(!super::consumed_on_load(&SavedGame::Campaign(Box::new(
+            screen
+        ))));
+    }
+}
@@ -1,3 +1,7 @@
+use adam_fov_rs::GridPoint;
+use bresenham::Bresenham;
+use macroquad::math::Vec2;
+
 use crate::mission::*;
 use crate::prelude::*;
 
@@ -8,6 +12,141 @@ pub enum SkillTargeting {
         max_range: u32,
         sprite: AnimationSpriteKind,
     },
+    // Everyone within radius of the targeted tile
+    Blast {
+        max_range: u32,
+        radius: u32,
+        sprite: AnimationSpriteKind,
+    },
+    // A quarter circle spreading out from the caster towards the target
+    Cone {
+        length: u32,
+    },
+    // Everyone from the caster towards the target until a wall
+    Line {
+        max_range: u32,
+        sprite: AnimationSpriteKind,
+    },
+    // Everyone around the caster
+    PointBlank {
+        radius: u32,
+    },
+}
+
+impl SkillTargeting {
+    pub fn is_area(&self) -> bool {
+        match self {
+            SkillTargeting::Caster | SkillTargeting::Ranged { .. } => false,
+            SkillTargeting::Blast { .. }
+            | SkillTargeting::Cone { .. }
+            | SkillTargeting::Line { .. }
+            | SkillTargeting::PointBlank { .. } => true,
+        }
+    }
+
+    // Tiles hit when used from source and aimed at target
+    pub fn affected_tiles(&self, level: &LevelState, source: Point, target: Point) -> Vec<Point> {
+        match self {
+            SkillTargeting::Caster => vec![source],
+            SkillTargeting::Ranged { .. } => vec![target],
+            SkillTargeting::Blast { radius, .. } => tiles_within(level, target, *radius),
+            SkillTargeting::PointBlank { radius } => tiles_within(level, source, *radius)
+                .into_iter()
+                .filter(|p| *p != source)
+                .collect(),
+            SkillTargeting::Cone { length } => {
+                if source == target {
+                    return vec![];
+                }
+                let to_vec = |p: Point| -> Vec2 { p.into() };
+                let direction = to_vec(target - source).normalize();
+                tiles_within(level, source, *length)
+                    .into_iter()
+                    .filter(|p| {
+                        *p != source
+                            // 45 degrees either side of the aim
+                            && to_vec(*p - source).normalize().dot(direction)
+                                >= std::f32::consts::FRAC_1_SQRT_2 - f32::EPSILON
+                    })
+                    .collect()
+            }
+            SkillTargeting::Line { max_range, .. } => {
+                let delta = target - source;
+                let steps = delta.x.abs().max(delta.y.abs());
+                if steps == 0 {
+                    return vec![];
+                }
+                // Extend the aim out past the target so the line covers the full range
+                let scale = (*max_range as i32 + steps - 1) / steps;
+                let end = source + Point::new(delta.x * scale, delta.y * scale);
+
+                Bresenham::new(
+                    (source.x as isize, source.y as isize),
+                    (end.x as isize, end.y as isize),
+                )
+                .skip(1)
+                .map(|(x, y)| Point::new(x as i32, y as i32))
+                .take_while(|p| {
+                    p.king_dist(source) < *max_range as usize && level.character_can_enter(*p)
+                })
+                .collect()
+            }
+        }
+    }
+
+    // How a skill aimed at target gets resolved, with a projectile for those that have one
+    pub fn area_skill_response(
+        &self,
+        level: &LevelState,
+        caster: &Character,
+        target: Point,
+        skill_name: &str,
+    ) -> HandleInputResponse {
+        let action = RequestedAction::UseAreaSkill {
+            source: caster.id,
+            target,
+            skill_name: skill_name.to_string(),
+        };
+        let projectile = match self {
+            SkillTargeting::Blast { sprite, .. } => Some((target, sprite)),
+            SkillTargeting::Line { sprite, .. } => self
+                .affected_tiles(level, caster.position, target)
+                .last()
+                .map(|end| (*end, sprite)),
+            _ => None,
+        };
+
+        match projectile {
+            Some((end, sprite)) => {
+                HandleInputResponse::ChangeActor(CurrentActor::Animation(AnimationInfo::new(
+                    caster.position,
+                    end,
+                    level,
+                    sprite.clone(),
+                    action,
+                    level.is_player(caster.id),
+                )))
+            }
+            None => HandleInputResponse::Action(Some(action)),
+        }
+    }
+}
+
+// Open tiles within radius that can be seen from the center
+fn tiles_within(level: &LevelState, center: Point, radius: u32) -> Vec<Point> {
+    let radius = radius as i32;
+    let visibility = level.map.compute_visibility(center);
+
+    let mut tiles = vec![];
+    for x in (center.x - radius)..=(center.x + radius) {
+        for y in (center.y - radius)..=(center.y + radius) {
+            let p = Point::new(x, y);
+            if level.character_can_enter(p) && visibility.get(p) {
+                tiles.push(p);
+            }
+        }
+    }
+    tiles
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -36,3 +175,52 @@ pub struct Skill {
     pub effect: Effect,
     pub targeting: SkillTargeting,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    fn sprite() -> AnimationSpriteKind {
+        AnimationSpriteKind::SingleFrame(Point::zero())
+    }
+
+    #[test]
+    fn area_shapes() {
+        let (_, level) = create_test_map();
+        let source = Point::new(1, 1);
+        let column = |ys: &[i32]| ys.iter().map(|y| Point::new(1, *y)).collect::<Vec<_>>();
+
+        let blast = SkillTargeting::Blast {
+            max_range: 6,
+            radius: 1,
+            sprite: sprite(),
+        };
+        assert_eq!(
+            blast.affected_tiles(&level, source, Point::new(1, 3)),
+            column(&[2, 3, 4])
+        );
+
+        let point_blank = SkillTargeting::PointBlank { radius: 2 };
+        assert_eq!(
+            point_blank.affected_tiles(&level, source, source),
+            column(&[2, 3])
+        );
+
+        let cone = SkillTargeting::Cone { length: 3 };
+        assert_eq!(
+            cone.affected_tiles(&level, source, Point::new(1, 5)),
+            column(&[2, 3, 4])
+        );
+
+        // Lines keep going past where they are aimed until they hit a wall
+        let line = SkillTargeting::Line {
+            max_range: 10,
+            sprite: sprite(),
+        };
+        assert_eq!(
+            line.affected_tiles(&level, source, Point::new(1, 2)),
+            column(&[2, 3, 4, 5])
+        );
+    }
+}
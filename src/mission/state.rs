@@ -1,6 +1,5 @@
-use std::path::PathBuf;
-
 use macroquad::input::{is_key_down, is_quit_requested};
+use macroquad::time::get_frame_time;
 
 use crate::campaign::CampaignScreenState;
 use crate::campaign::CampaignState;
@@ -19,23 +18,50 @@ pub struct MissionState {
     pub mission_complete: bool,
     pub campaign: CampaignState,
     pub active_rune: RuneKinds,
+
+    // Holds a whole copy of the mission, so it is saved next to the slot instead of in it
+    #[serde(skip)]
+    pub replay: Option<Replay>,
+
+    #[serde(default)]
+    pub controller: PlayerController,
+
+    // Every floor of the mission, with the one being played taken out into level.
+    // Deeper floors stay None until the player first reaches them
+    #[serde(default)]
+    pub floors: Vec<Option<LevelState>>,
+    #[serde(default)]
+    pub depth: usize,
 }
 
 impl MissionState {
-    pub fn new(campaign: CampaignState, active_rune: RuneKinds) -> MissionState {
-        let level = generate_random_map(
+    pub fn new_with_seed(
+        campaign: CampaignState,
+        active_rune: RuneKinds,
+        seed: u64,
+    ) -> MissionState {
+        let floor_count = Self::floor_count(campaign.completed_mission_count(), seed);
+        let level = generate_floor(
             campaign.character.clone(),
             Self::map_difficulty(campaign.completed_mission_count()),
+            seed,
+            floor_count == 1,
         );
 
-        Self {
+        let mut state = Self {
             level,
             frame: 0,
             current_actor: CurrentActor::PlayerStandardAction,
             mission_complete: false,
             campaign,
             active_rune,
-        }
+            replay: None,
+            controller: PlayerController::Keyboard,
+            floors: vec![None; floor_count],
+            depth: 0,
+        };
+        state.replay = Some(Replay::new(&state));
+        state
     }
 
     fn map_difficulty(mission_count: u32) -> u32 {
@@ -46,10 +72,72 @@ impl MissionState {
         }
     }
 
+    // Later missions may go further down
+    fn floor_count(mission_count: u32, seed: u64) -> usize {
+        let most = match mission_count {
+            0 => 1,
+            1..=3 => 2,
+            _ => 3,
+        };
+        GameRng::new(seed).gen_range(1, most + 1) as usize
+    }
+
     pub fn process_frame(&mut self, screen: &mut Screen) -> Option<GameFlow> {
+        if cfg!(feature = "desktop") {
+            if is_quit_requested()
+                || (is_key_pressed(KeyCode::Q)
+                    && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)))
+            {
+                self.save_to_disk();
+                self.save_replay();
+                return Some(GameFlow::Quitting);
+            }
+        }
+        if is_key_pressed(KeyCode::H) {
+            return Some(GameFlow::Help(HelpState::new(GameFlow::Gameplay(
+                self.clone(),
+            ))));
+        }
+
+        #[cfg(debug_assertions)]
+        if is_key_pressed(KeyCode::F12) {
+            self.controller = match self.controller {
+                PlayerController::Keyboard => PlayerController::Autopilot,
+                PlayerController::Autopilot => PlayerController::Keyboard,
+            };
+            screen.push_floating_text(&format!("Controller: {:?}", self.controller));
+        }
+
+        self.campaign.playtime += get_frame_time() as f64;
+
+        if let Some(next) = self.process_turns(screen) {
+            // Dying ends the run, finishing the mission saves where we are in the campaign
+            match &next {
+                GameFlow::Campaign(campaign) => campaign.save_to_disk(),
+                _ => delete_save_slot(self.campaign.save_slot),
+            }
+            self.save_replay();
+            return Some(next);
+        }
+
+        if let Some(player) = self.level.get_player() {
+            screen.camera.update(player.position, self.frame);
+        }
+        self.level.render(screen);
+        self.current_actor.render(screen, &self.level);
+        None
+    }
+
+    // Runs actors until one needs to wait on input or an animation. Does not
+    // read any input itself, so with the autopilot it can run without a window
+    pub fn process_turns<S: ScreenInterface>(&mut self, screen: &mut S) -> Option<GameFlow> {
         if self.frame == 0 {
+            let location = match self.floors.len() {
+                0 | 1 => "".to_string(),
+                floors => format!(" from {} floors down", floors - 1),
+            };
             screen.push_extended_floating_text(&format!(
-                "Retrieve the {} rune and return. 'h' for help",
+                "Retrieve the {} rune{location} and return. 'h' for help",
                 self.active_rune
             ));
         }
@@ -57,22 +145,13 @@ impl MissionState {
         self.frame += 1;
 
         loop {
-            if cfg!(feature = "desktop") {
-                if is_quit_requested()
-                    || (is_key_pressed(KeyCode::Q)
-                        && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)))
-                {
-                    self.save_to_disk();
-                    return Some(GameFlow::Quitting);
+            if let Some(action) = self
+                .current_actor
+                .act(&mut self.level, self.controller, screen)
+            {
+                if let Some(replay) = &mut self.replay {
+                    replay.record(&action);
                 }
-            }
-            if is_key_pressed(KeyCode::H) {
-                return Some(GameFlow::Help(HelpState::new(GameFlow::Gameplay(
-                    self.clone(),
-                ))));
-            }
-
-            if let Some(action) = self.current_actor.act(&mut self.level, screen) {
                 self.process_action(action, screen);
             }
 
@@ -85,16 +164,12 @@ impl MissionState {
                 )));
             }
 
-            screen.camera.update(self.get_player().position, self.frame);
-
             // We continue looping until the current actor needs to wait
             if self.current_actor.needs_to_wait() {
                 break;
             }
         }
 
-        self.level.render(screen);
-        self.current_actor.render(&screen, &self.level);
         None
     }
 }
@@ -124,36 +199,103 @@ pub enum RequestedAction {
         target: CharacterId,
         skill_name: String,
     },
+    UseAreaSkill {
+        source: CharacterId,
+        target: Point,
+        skill_name: String,
+    },
     Wait(CharacterId),
+    // Indexes are into carried_items, except unequipping which is into equipped
+    UseItem {
+        source: CharacterId,
+        index: usize,
+    },
+    EquipItem {
+        source: CharacterId,
+        index: usize,
+    },
+    UnequipItem {
+        source: CharacterId,
+        index: usize,
+    },
+    DropItem {
+        source: CharacterId,
+        index: usize,
+    },
     Stairs,
     #[cfg(debug_assertions)]
     DebugMenu(DebugRequest),
 }
 
 impl MissionState {
-    pub fn get_player(&self) -> &Character {
-        self.level
-            .characters
-            .iter()
-            .find(|c| c.is_player())
-            .expect("Player must still exist")
+    // The mission's seed is the one the top floor was built from
+    pub fn seed(&self) -> u64 {
+        match self.floors.first() {
+            Some(Some(top)) => top.rng.seed(),
+            _ => self.level.rng.seed(),
+        }
     }
 
-    pub fn get_player_mut(&mut self) -> &mut Character {
-        self.level
-            .characters
-            .iter_mut()
-            .find(|c| c.is_player())
-            .expect("Player must still exist")
+    // Leaves the current floor as it is, to be picked up again when the player comes back
+    pub fn change_floor(&mut self, depth: usize) {
+        let going_down = depth > self.depth;
+        let seed = self.seed();
+        let player_id = self.level.player_id();
+        let Some(player_index) = self.level.characters.iter().position(|c| c.id == player_id)
+        else {
+            return;
+        };
+        let mut player = self.level.characters.remove(player_index);
+
+        let next = match self.floors[depth].take() {
+            Some(mut floor) => {
+                // Arrive on the stairs leading back to where we came from
+                let stairs = if going_down {
+                    TileKind::Exit
+                } else {
+                    TileKind::StairsDown
+                };
+                let arrival = floor
+                    .map
+                    .tiles()
+                    .into_iter()
+                    .find(|p| floor.map.get(*p).kind == stairs)
+                    .expect("Floor should have stairs back");
+                player.position = std::iter::once(arrival)
+                    .chain(arrival.adjacent())
+                    .find(|p| {
+                        floor.character_can_enter(*p)
+                            && floor.find_character_at_position(*p).is_none()
+                    })
+                    .unwrap_or(arrival);
+                floor.characters.push(player);
+                floor
+            }
+            None => generate_floor(
+                player,
+                Self::map_difficulty(self.campaign.completed_mission_count()),
+                seed.wrapping_add(depth as u64),
+                depth == self.floors.len() - 1,
+            ),
+        };
+
+        let mut previous = std::mem::replace(&mut self.level, next);
+        self.level.turn_log = std::mem::take(&mut previous.turn_log);
+        self.floors[self.depth] = Some(previous);
+        self.depth = depth;
+        self.level.update_visibility();
     }
 
+    // Nobody left to play counts too
     pub fn is_player_dead(&self) -> bool {
-        self.get_player().health.is_dead()
+        self.level
+            .get_player()
+            .is_none_or(|player| player.health.is_dead())
     }
 
     // Screen used in debug
     #[allow(unused_variables)]
-    fn process_action(&mut self, action: RequestedAction, screen: &mut Screen) {
+    pub fn process_action<S: ScreenInterface>(&mut self, action: RequestedAction, screen: &mut S) {
         match action {
             RequestedAction::Move(id, dest) => {
                 move_character(self, id, dest, screen);
@@ -173,9 +315,28 @@ impl MissionState {
                 target,
                 skill_name,
             } => {
-                apply_skill(self, source, target, &skill_name, screen);
+                apply_skill(
+                    self,
+                    source,
+                    SkillTarget::Character(target),
+                    &skill_name,
+                    screen,
+                );
             }
-            RequestedAction::Stairs => ascend_stars(self, screen),
+            RequestedAction::UseAreaSkill {
+                source,
+                target,
+                skill_name,
+            } => {
+                apply_skill(self, source, SkillTarget::Tile(target), &skill_name, screen);
+            }
+            RequestedAction::UseItem { source, index } => use_item(self, source, index, screen),
+            RequestedAction::EquipItem { source, index } => equip_item(self, source, index, screen),
+            RequestedAction::UnequipItem { source, index } => {
+                unequip_item(self, source, index, screen)
+            }
+            RequestedAction::DropItem { source, index } => drop_item(self, source, index, screen),
+            RequestedAction::Stairs => take_stairs(self, screen),
             #[cfg(debug_assertions)]
             RequestedAction::DebugMenu(command) => {
                 screen.push_floating_text(&format!("Running debug command: {command:?}"));
@@ -197,12 +358,14 @@ impl MissionState {
                         self.mission_complete = true;
                     }
                     DebugRequest::Heal => {
-                        let player = self.get_player_mut();
-                        player.health.current = player.health.max;
-                        player.will.current = player.will.max;
+                        if let Some(player) = self.level.get_player_mut() {
+                            player.health.current = player.health.max;
+                            player.will.current = player.will.max;
+                        }
                     }
                     DebugRequest::ClearLevel => {
-                        self.level.characters.retain(|c| c.is_player());
+                        let player = self.level.player_id();
+                        self.level.characters.retain(|c| c.id == player);
                     }
                     DebugRequest::SpawnEnemy(level) => {
                         use adam_fov_rs::GridPoint;
@@ -212,7 +375,9 @@ impl MissionState {
                         let enemies = data.get_enemies_at_level(level);
                         let enemy = enemies.choose().unwrap();
                         let mut enemy = data.get_character(enemy);
-                        let player = self.level.get_player();
+                        let Some(player) = self.level.get_player() else {
+                            return;
+                        };
                         let tiles = self.level.map.tiles();
                         let close_tiles = tiles
                             .iter()
@@ -225,7 +390,7 @@ impl MissionState {
 
                         if let Some(position) = close_tiles.choose() {
                             enemy.position = **position;
-                            self.level.characters.push(enemy);
+                            self.level.add_character(enemy);
                         }
                     }
                 }
@@ -233,69 +398,151 @@ impl MissionState {
         }
     }
 
-    pub fn save_to_string(&self) -> String {
-        serde_json::to_string(self).expect("Unable to save game")
+    pub fn save_replay(&self) {
+        if let Some(replay) = &self.replay {
+            replay.save_to_disk();
+        }
     }
 
-    #[cfg(feature = "desktop")]
-    pub fn savefile_name() -> PathBuf {
-        let dirs = directories::ProjectDirs::from("com", "", "Archivist")
-            .expect("Unable to find project directory?");
-        let mut path = dirs.data_dir().to_path_buf();
-        path.push("game.sav");
-        path
+    pub fn save_to_string(&self) -> String {
+        serde_json::to_string(self).expect("Unable to save game")
     }
 
-    #[cfg(not(feature = "desktop"))]
-    pub fn savefile_name() -> PathBuf {
-        PathBuf::new()
+    pub fn save_to_disk(&self) {
+        write_save_slot(&self.campaign, SavedGame::Mission(Box::new(self.clone())));
     }
+}
 
-    pub fn savefile_exists() -> bool {
-        let filename = Self::savefile_name();
-
-        match std::fs::exists(&filename) {
-            Ok(exists) => exists,
-            Err(_) => false,
+#[cfg(test)]
+mod tests {
+    use crate::Difficulty;
+    use crate::campaign::{CampaignState, RuneKinds, load_equipment_options};
+    use crate::mission::*;
+    use crate::prelude::*;
+
+    fn play_mission(seed: u64) -> (bool, usize, i32, Point) {
+        let player = load_equipment_options()[0].outfit_character(Difficulty::Normal);
+
+        let mut state =
+            MissionState::new_with_seed(CampaignState::new(player), RuneKinds::Fire, seed);
+        state.replay = None;
+        state.controller = PlayerController::Autopilot;
+
+        let mut screen = EmptyScreen {};
+        for _ in 0..5000 {
+            if let Some(flow) = state.process_turns(&mut screen) {
+                let player = state.level.get_player().unwrap();
+                return (
+                    matches!(flow, GameFlow::Campaign(_)),
+                    state.frame,
+                    player.health.current,
+                    player.position,
+                );
+            }
         }
+        panic!("Mission did not finish");
     }
 
-    pub fn save_to_disk(&self) {
-        let filename = Self::savefile_name();
+    #[test]
+    fn headless_mission_is_deterministic() {
+        assert_eq!(play_mission(3), play_mission(3));
+    }
 
-        match std::fs::create_dir_all(filename.parent().expect("Project dir should be longer")) {
-            Ok(()) => {
-                if let Err(e) = std::fs::write(filename, self.save_to_string()) {
-                    eprintln!("Unable to save game: {e:?}");
-                }
-            }
-            Err(e) => {
-                eprintln!("Unable to create game location: {e:?}");
+    #[test]
+    fn reloaded_mission_keeps_its_player_on_new_floors() {
+        let player = load_equipment_options()[0].outfit_character(Difficulty::Normal);
+        let mut state = MissionState::new_with_seed(CampaignState::new(player), RuneKinds::Fire, 3);
+        state.floors = vec![None, None];
+
+        // A restarted game counts ids up from the start again, and may reach the player's
+        let mut save: serde_json::Value = serde_json::from_str(&state.save_to_string()).unwrap();
+        let player_id = save["level"]["player"].clone();
+        let reused = serde_json::to_value(CharacterId::next().after()).unwrap();
+        for character in save["level"]["characters"].as_array_mut().unwrap() {
+            if character["id"] == player_id {
+                character["id"] = reused.clone();
             }
         }
+        save["level"]["player"] = reused;
+        let mut state: MissionState = serde_json::from_value(save).unwrap();
+
+        state.change_floor(1);
+        let player = state.level.get_player().unwrap();
+        assert_eq!(player.faction, Faction::Player);
+        assert_eq!(player.name, "Player");
+        let mut ids = state
+            .level
+            .characters
+            .iter()
+            .map(|c| c.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), state.level.characters.len());
     }
 
-    pub fn delete_any_save() {
-        let filename = Self::savefile_name();
-        if std::fs::remove_file(filename).is_err() {
-            eprintln!("Unable to delete game after load.");
-        }
+    fn assert_unique_ids(level: &LevelState) {
+        let mut ids = level.characters.iter().map(|c| c.id).collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), level.characters.len());
     }
 
-    pub fn load_from_disk() -> Option<Self> {
-        let filename = Self::savefile_name();
-        if let Ok(text) = std::fs::read(&filename) {
-            match serde_json::from_slice(&text) {
-                Ok(state) => {
-                    Self::delete_any_save();
-                    return Some(state);
-                }
-                Err(e) => {
-                    eprintln!("Unable to load game: {e:?}");
-                }
-            }
+    #[test]
+    fn generated_floors_never_reuse_the_player_id() {
+        for seed in 0..5 {
+            // Ahead of the counter, as for a player made before the game was restarted
+            let mut player = load_equipment_options()[0].outfit_character(Difficulty::Normal);
+            player.id = CharacterId::next().after();
+            let level = generate_floor(player, 2, seed, false);
+            let player = level.get_player().unwrap();
+            assert_eq!(player.faction, Faction::Player);
+            assert_eq!(player.name, "Player");
+            assert_unique_ids(&level);
         }
+    }
 
-        None
+    #[test]
+    fn floors_keep_their_state() {
+        let player = load_equipment_options()[0].outfit_character(Difficulty::Normal);
+        let mut state = MissionState::new_with_seed(CampaignState::new(player), RuneKinds::Fire, 3);
+        state.floors = vec![None, None];
+        state.level.items.clear();
+
+        // Use the entrance as the way down, so the top floor has no way out
+        let top_stairs = state.level.get_player().unwrap().position;
+        let mut tile = state.level.map.get(top_stairs);
+        tile.kind = TileKind::StairsDown;
+        state.level.map.set(top_stairs, tile);
+        let top_monsters = state.level.characters.len();
+
+        take_stairs(&mut state, &mut EmptyScreen {});
+        assert_eq!(state.depth, 1);
+        assert_unique_ids(&state.level);
+        assert!(state.level.items.iter().any(|(_, i)| i.name == "Runestone"));
+        let bottom_stairs = state.level.get_player().unwrap().position;
+        assert_eq!(state.level.map.get(bottom_stairs).kind, TileKind::Exit);
+
+        let monster = state
+            .level
+            .characters
+            .iter()
+            .find(|c| !state.level.is_player(c.id))
+            .unwrap()
+            .id;
+        state.level.remove_character(monster);
+        let bottom_monsters = state.level.characters.len();
+
+        take_stairs(&mut state, &mut EmptyScreen {});
+        assert_eq!(state.depth, 0);
+        assert_eq!(state.level.get_player().unwrap().position, top_stairs);
+        assert_eq!(state.level.characters.len(), top_monsters);
+        assert_eq!(state.seed(), 3);
+
+        take_stairs(&mut state, &mut EmptyScreen {});
+        assert_eq!(state.depth, 1);
+        assert_eq!(state.level.characters.len(), bottom_monsters);
+        assert_unique_ids(&state.level);
+        assert!(state.level.find_character(monster).is_none());
     }
 }
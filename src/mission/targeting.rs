@@ -45,7 +45,7 @@ impl BlinkInfo {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TargetSkill {
     pub skill_name: String,
-    pub spite: AnimationSpriteKind,
+    pub targeting: SkillTargeting,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -71,7 +71,7 @@ impl TargetingInfo {
     pub fn handle_input(
         &mut self,
         level: &LevelState,
-        screen: &Screen,
+        camera_origin: Point,
         is_current_target_valid: bool,
     ) -> HandleInputResponse {
         self.tick();
@@ -83,23 +83,7 @@ impl TargetingInfo {
             || is_mouse_button_released(MouseButton::Left)
         {
             if is_current_target_valid {
-                if let Some(target) = level.find_character_at_position(self.position) {
-                    let player = level.get_player();
-                    HandleInputResponse::ChangeActor(CurrentActor::Animation(AnimationInfo::new(
-                        player.position,
-                        target.position,
-                        level,
-                        self.target_skill.spite.clone(),
-                        RequestedAction::UseSkill {
-                            source: level.get_player().id,
-                            target: target.id,
-                            skill_name: self.target_skill.skill_name.clone(),
-                        },
-                        true,
-                    )))
-                } else {
-                    HandleInputResponse::Action(None)
-                }
+                self.confirm_target(level)
             } else {
                 HandleInputResponse::Action(None)
             }
@@ -108,17 +92,19 @@ impl TargetingInfo {
             HandleInputResponse::Action(None)
         } else if mouse_delta_position().length() > 0.0 {
             let mouse = mouse_position();
-            let x = (mouse.0 / 24.0).floor() as i32 + screen.camera.left_x;
-            let y = (mouse.1 / 24.0).floor() as i32 + screen.camera.top_y;
+            let x = (mouse.0 / 24.0).floor() as i32 + camera_origin.x;
+            let y = (mouse.1 / 24.0).floor() as i32 + camera_origin.y;
             self.set_position(Point::new(x, y));
             HandleInputResponse::Action(None)
-        } else if is_key_pressed(KeyCode::Tab) {
-            let player_position = level.get_player().position;
+        } else if is_key_pressed(KeyCode::Tab)
+            && let Some(player) = level.get_player()
+        {
+            let player_position = player.position;
             let visibility = level.map.compute_visibility(player_position);
             let mut visible_enemies = level
                 .characters
                 .iter()
-                .filter(|c| !c.is_player() && visibility.get(c.position))
+                .filter(|c| c.is_hostile_to(player) && visibility.get(c.position))
                 .collect::<Vec<_>>();
             visible_enemies.sort_by_key(|e| player_position.king_dist(e.position));
 
@@ -152,11 +138,54 @@ impl TargetingInfo {
         }
     }
 
+    fn confirm_target(&self, level: &LevelState) -> HandleInputResponse {
+        let Some(player) = level.get_player() else {
+            return HandleInputResponse::Action(None);
+        };
+        match &self.target_skill.targeting {
+            SkillTargeting::Ranged { sprite, .. } => {
+                if let Some(target) = level.find_character_at_position(self.position) {
+                    HandleInputResponse::ChangeActor(CurrentActor::Animation(AnimationInfo::new(
+                        player.position,
+                        target.position,
+                        level,
+                        sprite.clone(),
+                        RequestedAction::UseSkill {
+                            source: player.id,
+                            target: target.id,
+                            skill_name: self.target_skill.skill_name.clone(),
+                        },
+                        true,
+                    )))
+                } else {
+                    HandleInputResponse::Action(None)
+                }
+            }
+            targeting => targeting.area_skill_response(
+                level,
+                player,
+                self.position,
+                &self.target_skill.skill_name,
+            ),
+        }
+    }
+
     pub fn tick(&mut self) {
         self.blink.tick();
     }
 
     pub fn render(&self, screen: &Screen, level: &LevelState) {
+        // Preview everything an area skill would hit
+        if self.target_skill.targeting.is_area() {
+            for tile in self.target_skill.targeting.affected_tiles(
+                level,
+                self.source_position,
+                self.position,
+            ) {
+                screen.draw_targeting(tile, ORANGE);
+            }
+        }
+
         if self.blink.should_draw() {
             let color = if CurrentActor::is_current_target_valid(&self, level) {
                 WHITE
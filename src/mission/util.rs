@@ -68,7 +68,11 @@ pub fn handle_movement_key() -> Option<Point> {
 }
 
 pub fn handle_move_bump(actor: &Character, dest: Point, level: &LevelState) -> RequestedAction {
-    if let Some(target) = level.find_character_at_position(dest) {
+    // Walking into an ally is handled by the move, which swaps places
+    if let Some(target) = level
+        .find_character_at_position(dest)
+        .filter(|c| c.is_hostile_to(actor))
+    {
         RequestedAction::WeaponAttack {
             source: actor.id,
             target: target.id,
@@ -79,12 +83,18 @@ pub fn handle_move_bump(actor: &Character, dest: Point, level: &LevelState) -> R
     }
 }
 
+#[cfg(test)]
+pub fn create_test_player() -> Character {
+    let mut player = Data::load().unwrap().get_character("Bat");
+    player.faction = Faction::Player;
+    player
+}
+
 #[cfg(test)]
 pub fn create_test_map() -> (CharacterId, LevelState) {
     let data = Data::load().unwrap();
 
-    let mut player = data.get_character("Bat");
-    player.name = "Player".to_string();
+    let mut player = create_test_player();
     player.position = Point::new(1, 1);
 
     let mut bat = data.get_character("Bat");
@@ -102,6 +112,6 @@ pub fn create_test_map() -> (CharacterId, LevelState) {
             },
         );
     }
-    let level = LevelState::new(map, vec![player, bat], vec![]);
+    let level = LevelState::new(map, player.id, vec![player, bat], vec![]);
     (id, level)
 }
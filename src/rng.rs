@@ -0,0 +1,106 @@
+use std::cell::Cell;
+
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_INC: u64 = 1442695040888963407;
+const MULTIPLIER: u64 = 6364136223846793005;
+
+// The same PCG generator macroquad uses, but with state we can save
+// so that a seed plus a list of actions always plays out the same way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRng {
+    seed: u64,
+    state: Cell<u64>,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        let rng = Self {
+            seed,
+            state: Cell::new(0),
+        };
+        rng.rand();
+        rng.state.set(rng.state.get().wrapping_add(seed));
+        rng.rand();
+        rng
+    }
+
+    pub fn from_time() -> Self {
+        Self::new(macroquad::miniquad::date::now() as u64)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rand(&self) -> u32 {
+        let old_state = self.state.get();
+        self.state
+            .set(old_state.wrapping_mul(MULTIPLIER).wrapping_add(DEFAULT_INC));
+        let xor_shifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rot = (old_state >> 59) as u32;
+        xor_shifted.rotate_right(rot)
+    }
+
+    // Returns a value in [low, high)
+    pub fn gen_range(&self, low: i32, high: i32) -> i32 {
+        let r = self.rand() as f64 / (u32::MAX as f64 + 1.0);
+        (low as f64 + (high as f64 - low as f64) * r) as i32
+    }
+
+    // Returns true with a probability of chance (0.0 - 1.0)
+    pub fn gen_chance(&self, chance: f64) -> bool {
+        (self.rand() as f64 / (u32::MAX as f64 + 1.0)) < chance
+    }
+
+    pub fn choose<'a, T>(&self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            items.get(self.gen_range(0, items.len() as i32) as usize)
+        }
+    }
+
+    // Map builders use macroquad's generator, so hand them one seeded from our stream
+    pub fn generator(&self) -> RandGenerator {
+        let rng = RandGenerator::new();
+        rng.srand(((self.rand() as u64) << 32) | self.rand() as u64);
+        rng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let first = GameRng::new(42);
+        let second = GameRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(first.gen_range(0, 1000), second.gen_range(0, 1000));
+        }
+    }
+
+    #[test]
+    fn restored_rng_continues_sequence() {
+        let rng = GameRng::new(7);
+        for _ in 0..10 {
+            rng.rand();
+        }
+
+        let restored: GameRng =
+            serde_json::from_str(&serde_json::to_string(&rng).unwrap()).unwrap();
+        assert_eq!(restored.seed(), 7);
+        for _ in 0..100 {
+            assert_eq!(rng.rand(), restored.rand());
+        }
+    }
+}
@@ -178,7 +178,7 @@ impl Music {
     }
 }
 
-// So we can test things with out sounds
+// So we can run the engine with out sounds or a window
 pub trait ScreenInterface {
     fn current_music_track(&mut self) -> Option<usize>;
 
@@ -189,12 +189,24 @@ pub trait ScreenInterface {
     fn set_music_volume(&mut self, volume: f32);
 
     fn play_sound(&mut self, name: &str);
+
+    fn push_floating_text(&mut self, text: &str);
+
+    fn push_extended_floating_text(&mut self, text: &str);
+
+    fn is_player_resting(&self) -> bool;
+
+    fn set_player_resting(&mut self, resting: bool);
+
+    // Top left map position currently shown, used to map the mouse onto the map
+    fn camera_origin(&self) -> Point;
+
+    // Resolve animations immediately instead of playing them out over frames
+    fn skip_animations(&self) -> bool;
 }
 
-#[cfg(test)]
 pub struct EmptyScreen {}
 
-#[cfg(test)]
 impl ScreenInterface for EmptyScreen {
     fn play_music_track(&mut self, _index: usize) {}
 
@@ -204,6 +216,24 @@ impl ScreenInterface for EmptyScreen {
 
     fn play_sound(&mut self, _name: &str) {}
 
+    fn push_floating_text(&mut self, _text: &str) {}
+
+    fn push_extended_floating_text(&mut self, _text: &str) {}
+
+    fn is_player_resting(&self) -> bool {
+        false
+    }
+
+    fn set_player_resting(&mut self, _resting: bool) {}
+
+    fn camera_origin(&self) -> Point {
+        Point::zero()
+    }
+
+    fn skip_animations(&self) -> bool {
+        true
+    }
+
     fn current_music_track(&mut self) -> Option<usize> {
         None
     }
@@ -442,4 +472,28 @@ impl ScreenInterface for Screen {
     fn set_music_volume(&mut self, volume: f32) {
         self.music.set_music_volume(volume);
     }
+
+    fn push_floating_text(&mut self, text: &str) {
+        Screen::push_floating_text(self, text);
+    }
+
+    fn push_extended_floating_text(&mut self, text: &str) {
+        Screen::push_extended_floating_text(self, text);
+    }
+
+    fn is_player_resting(&self) -> bool {
+        self.is_player_resting
+    }
+
+    fn set_player_resting(&mut self, resting: bool) {
+        self.is_player_resting = resting;
+    }
+
+    fn camera_origin(&self) -> Point {
+        Point::new(self.camera.left_x, self.camera.top_y)
+    }
+
+    fn skip_animations(&self) -> bool {
+        false
+    }
 }
@@ -43,6 +43,13 @@ impl DeathState {
             screen_height() / 2.0,
             Some(GRAY),
         );
+        // So a run worth another try can be replayed from Custom Seed
+        Screen::draw_centered_text(
+            &self.mission_state.campaign.setup().describe(),
+            22,
+            screen_height() / 2.0 + 40.0,
+            Some(GRAY),
+        );
 
         if self.frame > 10 && get_keys_pressed().iter().len() > 0 {
             Some(GameFlow::Title(TitleState::new()))
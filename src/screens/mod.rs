@@ -2,5 +2,8 @@ pub mod credits;
 pub mod death;
 pub mod help;
 pub mod options;
+pub mod replay;
+pub mod save_slots;
+pub mod seed_entry;
 pub mod title;
 pub mod victory;
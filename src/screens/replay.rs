@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+
+use macroquad::text::draw_text;
+
+use crate::mission::{CurrentActor, MissionState, Replay, RequestedAction};
+use crate::prelude::*;
+use crate::screens::title::TitleState;
+
+const REPLAY_FRAMES_PER_TURN: usize = 15;
+
+#[derive(Debug, Clone)]
+pub struct ReplayState {
+    mission: MissionState,
+    actions: VecDeque<RequestedAction>,
+    total_actions: usize,
+    frame: usize,
+    paused: bool,
+    fast_forward: bool,
+    finished: bool,
+    desynced: bool,
+}
+
+impl ReplayState {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            mission: *replay.start,
+            total_actions: replay.actions.len(),
+            actions: replay.actions.into(),
+            frame: 0,
+            paused: false,
+            fast_forward: false,
+            finished: false,
+            desynced: false,
+        }
+    }
+
+    pub fn process_frame(&mut self, screen: &mut Screen) -> Option<GameFlow> {
+        self.frame += 1;
+
+        if is_key_pressed(KeyCode::Escape) {
+            return Some(GameFlow::Title(TitleState::new()));
+        }
+
+        let mut step = false;
+        if is_key_pressed(KeyCode::Space) {
+            self.paused = !self.paused;
+        } else if is_key_pressed(KeyCode::F) {
+            self.fast_forward = !self.fast_forward;
+        } else if is_key_pressed(KeyCode::Period) || is_key_pressed(KeyCode::Right) {
+            self.paused = true;
+            step = true;
+        }
+
+        let is_animating = matches!(self.mission.current_actor, CurrentActor::Animation(_));
+        let turn_ready = self.fast_forward || self.frame.is_multiple_of(REPLAY_FRAMES_PER_TURN);
+        if !self.finished && (step || (!self.paused && (is_animating || turn_ready))) {
+            self.advance(screen);
+        }
+
+        if let Some(player) = self.mission.level.get_player() {
+            screen.camera.update(player.position, self.frame);
+        }
+        self.mission.level.render(screen);
+        self.mission
+            .current_actor
+            .render(screen, &self.mission.level);
+        self.draw_status();
+
+        None
+    }
+
+    // Plays the next recorded player turn, and then lets every other actor
+    // act until it is the player's turn again (or an animation needs frames)
+    fn advance(&mut self, screen: &mut Screen) {
+        let mut played_player_turn = false;
+
+        loop {
+            match &self.mission.current_actor {
+                CurrentActor::PlayerStandardAction
+                | CurrentActor::PlayerTargeting(_)
+                | CurrentActor::PlayerInventory(_) => {
+                    if played_player_turn {
+                        break;
+                    }
+                    let Some(action) = self.actions.pop_front() else {
+                        self.finished = true;
+                        break;
+                    };
+                    self.mission.current_actor = CurrentActor::PlayerStandardAction;
+                    self.mission.level.turn_log.clear();
+                    self.mission.process_action(action, screen);
+                    played_player_turn = true;
+                }
+                CurrentActor::EnemyAction(_) | CurrentActor::Animation(_) => {
+                    if let Some(action) = self.mission.current_actor.act(
+                        &mut self.mission.level,
+                        self.mission.controller,
+                        screen,
+                    ) {
+                        // Enemies are re-run with the same seed, so they should
+                        // match what was recorded unless the engine has changed.
+                        // Past that point the recording no longer lines up with the turns
+                        if self.actions.pop_front().as_ref() != Some(&action) {
+                            self.desynced = true;
+                            self.finished = true;
+                            self.paused = true;
+                            break;
+                        }
+                        self.mission.process_action(action, screen);
+                    }
+                    if matches!(self.mission.current_actor, CurrentActor::Animation(_)) {
+                        break;
+                    }
+                }
+            }
+
+            if self.mission.is_player_dead() || self.mission.mission_complete {
+                self.finished = true;
+                break;
+            }
+        }
+    }
+
+    fn draw_status(&self) {
+        let state = if self.finished {
+            "Finished"
+        } else if self.paused {
+            "Paused"
+        } else if self.fast_forward {
+            "Fast Forward"
+        } else {
+            "Playing"
+        };
+
+        draw_text(
+            &format!(
+                "Replay (Seed {}) - {state} - {}/{} actions",
+                self.mission.seed(),
+                self.total_actions - self.actions.len(),
+                self.total_actions
+            ),
+            10.0,
+            60.0,
+            20.0,
+            WHITE,
+        );
+        draw_text(
+            "Space - Pause  . - Step  F - Fast Forward  Esc - Exit",
+            10.0,
+            80.0,
+            20.0,
+            WHITE,
+        );
+        if self.desynced {
+            Screen::draw_centered_text(
+                "Replay no longer matches this version of the game",
+                22,
+                120.0,
+                Some(RED),
+            );
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use macroquad::window::screen_height;
+
+use crate::campaign::{CampaignScreenState, RunSetup};
+use crate::mission::{SaveMetadata, delete_save_slot, load_save_slot_metadata};
+use crate::prelude::*;
+use crate::screens::title::TitleState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveSlotsMode {
+    Load,
+    NewGame,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingConfirm {
+    Overwrite,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub struct SaveSlotsState {
+    mode: SaveSlotsMode,
+    slots: Vec<Option<Result<SaveMetadata, String>>>,
+    selection: usize,
+    confirm: Option<PendingConfirm>,
+    error: Option<String>,
+    // The seed a new game in the picked slot will use
+    setup: RunSetup,
+}
+
+impl SaveSlotsState {
+    pub fn new(mode: SaveSlotsMode) -> Self {
+        let slots = load_save_slot_metadata();
+
+        // Start on the first slot that makes sense for what we are doing
+        let selection = match mode {
+            SaveSlotsMode::Load => slots.iter().position(|s| s.is_some()),
+            SaveSlotsMode::NewGame => slots.iter().position(|s| s.is_none()),
+        }
+        .unwrap_or(0);
+
+        Self {
+            mode,
+            slots,
+            selection,
+            confirm: None,
+            error: None,
+            setup: RunSetup::random(),
+        }
+    }
+
+    pub fn new_game(setup: RunSetup) -> Self {
+        Self {
+            setup,
+            ..Self::new(SaveSlotsMode::NewGame)
+        }
+    }
+
+    // Back to the browser when a save could not be loaded, so the player knows why
+    pub fn with_error(mode: SaveSlotsMode, slot: usize, error: String) -> Self {
+        Self {
+            selection: slot,
+            error: Some(error),
+            ..Self::new(mode)
+        }
+    }
+
+    pub fn process_frame(&mut self) -> Option<GameFlow> {
+        let title = match self.mode {
+            SaveSlotsMode::Load => "Load Game",
+            SaveSlotsMode::NewGame => "New Game - Choose a Slot",
+        };
+        Screen::draw_centered_text(title, 48, 75.0, None);
+
+        let mut offset = 250.0;
+        for (i, slot) in self.slots.iter().enumerate() {
+            let (color, background) = self.slot_color_line(i);
+            Screen::draw_centered_text_with_color(
+                &Self::slot_text(i, slot),
+                32,
+                offset,
+                color,
+                background,
+            );
+            offset += 50.0;
+        }
+
+        let prompt = match self.confirm {
+            Some(PendingConfirm::Overwrite) => Some("Press Enter again to overwrite this save"),
+            Some(PendingConfirm::Delete) => Some("Press Delete again to delete this save"),
+            None => None,
+        };
+        if let Some(prompt) = prompt {
+            Screen::draw_centered_text_with_color(prompt, 22, offset + 25.0, RED, None);
+        } else if let Some(error) = &self.error {
+            Screen::draw_centered_text_with_color(error, 22, offset + 25.0, RED, None);
+        } else if let Some(Some(Err(error))) = self.slots.get(self.selection) {
+            Screen::draw_centered_text_with_color(error, 22, offset + 25.0, RED, None);
+        }
+
+        Screen::draw_centered_text(
+            "Enter - Select  Delete - Delete Save  Escape - Back",
+            22,
+            screen_height() - 40.0,
+            None,
+        );
+
+        if is_key_pressed(KeyCode::Down) {
+            if self.selection < self.slots.len() - 1 {
+                self.selection += 1;
+                self.confirm = None;
+                self.error = None;
+            }
+        } else if is_key_pressed(KeyCode::Up) {
+            if self.selection > 0 {
+                self.selection -= 1;
+                self.confirm = None;
+                self.error = None;
+            }
+        } else if is_key_pressed(KeyCode::Escape) {
+            if self.confirm.is_some() {
+                self.confirm = None;
+            } else {
+                return Some(GameFlow::Title(TitleState::new()));
+            }
+        } else if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+            if self.slots[self.selection].is_some() {
+                if self.confirm == Some(PendingConfirm::Delete) {
+                    delete_save_slot(self.selection);
+                    self.slots[self.selection] = None;
+                    self.confirm = None;
+                } else {
+                    self.confirm = Some(PendingConfirm::Delete);
+                }
+            }
+        } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            return self.select();
+        }
+
+        None
+    }
+
+    fn select(&mut self) -> Option<GameFlow> {
+        let used = self.slots[self.selection].is_some();
+        let readable = matches!(self.slots[self.selection], Some(Ok(_)));
+
+        match self.mode {
+            SaveSlotsMode::Load if readable => Some(CampaignScreenState::load_save(self.selection)),
+            _ if used && self.confirm != Some(PendingConfirm::Overwrite) => {
+                self.confirm = Some(PendingConfirm::Overwrite);
+                None
+            }
+            _ => {
+                delete_save_slot(self.selection);
+                Some(GameFlow::Campaign(CampaignScreenState::new(
+                    self.selection,
+                    self.setup.clone(),
+                )))
+            }
+        }
+    }
+
+    fn slot_text(index: usize, slot: &Option<Result<SaveMetadata, String>>) -> String {
+        match slot {
+            Some(Ok(metadata)) => {
+                let runes = if metadata.collected_runes.is_empty() {
+                    "None".to_string()
+                } else {
+                    metadata
+                        .collected_runes
+                        .iter()
+                        .map(|r| r.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                format!(
+                    "Slot {} - {} - Mission {} - Runes: {} - {} (v{})",
+                    index + 1,
+                    metadata.kit,
+                    metadata.mission_count,
+                    runes,
+                    metadata.playtime_text(),
+                    metadata.version
+                )
+            }
+            Some(Err(_)) => format!("Slot {} - Unable to load", index + 1),
+            None => format!("Slot {} - Empty", index + 1),
+        }
+    }
+
+    fn slot_color_line(&self, current: usize) -> (Color, Option<Color>) {
+        if current == self.selection {
+            (BLUE, Some(WHITE))
+        } else if self.slots[current].is_none() {
+            (GRAY, None)
+        } else if let Some(Err(_)) = self.slots[current] {
+            (RED, None)
+        } else {
+            (WHITE, None)
+        }
+    }
+}
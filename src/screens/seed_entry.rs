@@ -0,0 +1,67 @@
+use macroquad::input::get_char_pressed;
+use macroquad::window::screen_height;
+
+use crate::campaign::RunSetup;
+use crate::prelude::*;
+use crate::screens::save_slots::SaveSlotsState;
+use crate::screens::title::TitleState;
+
+// Long enough for any u64
+const MAX_SEED_DIGITS: usize = 19;
+
+#[derive(Debug, Clone)]
+pub struct SeedEntryState {
+    text: String,
+}
+
+impl Default for SeedEntryState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SeedEntryState {
+    pub fn new() -> Self {
+        // Drop anything typed before this screen opened
+        while get_char_pressed().is_some() {}
+        Self {
+            text: String::new(),
+        }
+    }
+
+    pub fn process_frame(&mut self) -> Option<GameFlow> {
+        Screen::draw_centered_text("Custom Seed", 48, 75.0, None);
+        Screen::draw_centered_text_with_color(
+            &format!("{}_", self.text),
+            48,
+            300.0,
+            BLUE,
+            Some(WHITE),
+        );
+        Screen::draw_centered_text(
+            "Type a number  Enter - Start  Escape - Back",
+            22,
+            screen_height() - 40.0,
+            None,
+        );
+
+        while let Some(c) = get_char_pressed() {
+            if c.is_ascii_digit() && self.text.len() < MAX_SEED_DIGITS {
+                self.text.push(c);
+            }
+        }
+
+        if is_key_pressed(KeyCode::Backspace) {
+            self.text.pop();
+        } else if is_key_pressed(KeyCode::Escape) {
+            return Some(GameFlow::Title(TitleState::new()));
+        } else if (is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter))
+            && let Ok(seed) = self.text.parse::<u64>()
+        {
+            return Some(GameFlow::SaveSlots(SaveSlotsState::new_game(
+                RunSetup::custom(seed),
+            )));
+        }
+        None
+    }
+}
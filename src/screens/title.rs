@@ -1,65 +1,82 @@
 use macroquad::text::draw_text;
 use macroquad::window::{screen_height, screen_width};
 
-use crate::campaign::CampaignScreenState;
-use crate::mission::MissionState;
+use crate::campaign::RunSetup;
+use crate::mission::any_save_slot_used;
 use crate::prelude::*;
 use crate::screens::help::HelpState;
 use crate::screens::options::OptionsState;
+use crate::screens::save_slots::{SaveSlotsMode, SaveSlotsState};
+use crate::screens::seed_entry::SeedEntryState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TitleOption {
+    LoadGame,
+    NewGame,
+    DailyChallenge,
+    CustomSeed,
+    Options,
+    Help,
+    Credits,
+    Quit,
+}
+
+impl TitleOption {
+    fn label(&self) -> &'static str {
+        match self {
+            TitleOption::LoadGame => "Load Game",
+            TitleOption::NewGame => "New Game",
+            TitleOption::DailyChallenge => "Daily Challenge",
+            TitleOption::CustomSeed => "Custom Seed",
+            TitleOption::Options => "Options",
+            TitleOption::Help => "Help",
+            TitleOption::Credits => "Credits",
+            TitleOption::Quit => "Quit",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TitleState {
-    has_save_game: bool,
+    // Looked up on the first frame shown, so making the screen touches no storage
+    has_save_game: Option<bool>,
     selection: usize,
 }
 
 impl TitleState {
     pub fn new() -> Self {
         TitleState {
-            has_save_game: MissionState::savefile_exists(),
+            has_save_game: None,
             selection: 0,
         }
     }
 
+    fn menu(&mut self) -> Vec<TitleOption> {
+        let mut menu = vec![];
+        if *self.has_save_game.get_or_insert_with(any_save_slot_used) {
+            menu.push(TitleOption::LoadGame);
+        }
+        menu.extend([
+            TitleOption::NewGame,
+            TitleOption::DailyChallenge,
+            TitleOption::CustomSeed,
+            TitleOption::Options,
+            TitleOption::Help,
+            TitleOption::Credits,
+            TitleOption::Quit,
+        ]);
+        menu
+    }
+
     pub fn process_frame(&mut self) -> Option<GameFlow> {
         Screen::draw_centered_text("The Archivist", 48, 75.0, None);
 
-        let mut offset = 500.0;
-        let mut next_option = 0;
-        if self.has_save_game {
-            let (color, background) = self.title_color_line(next_option);
-            Screen::draw_centered_text_with_color("Load Game", 48, offset, color, background);
+        let menu = self.menu();
+        let mut offset = 400.0;
+        for (i, option) in menu.iter().enumerate() {
+            let (color, background) = self.title_color_line(i);
+            Screen::draw_centered_text_with_color(option.label(), 48, offset, color, background);
             offset += 50.0;
-            next_option += 1;
-        }
-
-        {
-            let (color, background) = self.title_color_line(next_option);
-            Screen::draw_centered_text_with_color("New Game", 48, offset, color, background);
-            offset += 50.0;
-            next_option += 1;
-        }
-        {
-            let (color, background) = self.title_color_line(next_option);
-            Screen::draw_centered_text_with_color("Options", 48, offset, color, background);
-            offset += 50.0;
-            next_option += 1;
-        }
-        {
-            let (color, background) = self.title_color_line(next_option);
-            Screen::draw_centered_text_with_color("Help", 48, offset, color, background);
-            offset += 50.0;
-            next_option += 1;
-        }
-        {
-            let (color, background) = self.title_color_line(next_option);
-            Screen::draw_centered_text_with_color("Credits", 48, offset, color, background);
-            offset += 50.0;
-            next_option += 1;
-        }
-        {
-            let (color, background) = self.title_color_line(next_option);
-            Screen::draw_centered_text_with_color("Quit", 48, offset, color, background);
         }
 
         draw_text(
@@ -71,7 +88,7 @@ impl TitleState {
         );
 
         if is_key_pressed(KeyCode::Down) {
-            if self.selection < self.max_options() {
+            if self.selection < menu.len() - 1 {
                 self.selection += 1;
             }
         } else if is_key_pressed(KeyCode::Up) {
@@ -79,47 +96,27 @@ impl TitleState {
                 self.selection -= 1;
             }
         } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
-            if self.has_save_game {
-                match self.selection {
-                    0 => return Some(CampaignScreenState::load_save()),
-                    1 => {
-                        // If you start a new game delete the save anyway
-                        MissionState::delete_any_save();
-                        return Some(GameFlow::Campaign(CampaignScreenState::new()));
-                    }
-                    2 => return Some(GameFlow::Options(OptionsState::new())),
-                    3 => {
-                        return Some(GameFlow::Help(HelpState::new(GameFlow::Title(
-                            self.clone(),
-                        ))));
-                    }
-                    4 => return Some(GameFlow::Credits),
-                    5 | _ => return Some(GameFlow::Quitting),
+            return Some(match menu[self.selection] {
+                TitleOption::LoadGame => {
+                    GameFlow::SaveSlots(SaveSlotsState::new(SaveSlotsMode::Load))
                 }
-            } else {
-                match self.selection {
-                    0 => {
-                        return Some(GameFlow::Campaign(CampaignScreenState::new()));
-                    }
-                    1 => return Some(GameFlow::Options(OptionsState::new())),
-                    2 => {
-                        return Some(GameFlow::Help(HelpState::new(GameFlow::Title(
-                            self.clone(),
-                        ))));
-                    }
-                    3 => return Some(GameFlow::Credits),
-                    4 | _ => return Some(GameFlow::Quitting),
+                TitleOption::NewGame => {
+                    GameFlow::SaveSlots(SaveSlotsState::new(SaveSlotsMode::NewGame))
                 }
-            }
+                TitleOption::DailyChallenge => {
+                    GameFlow::SaveSlots(SaveSlotsState::new_game(RunSetup::daily()))
+                }
+                TitleOption::CustomSeed => GameFlow::SeedEntry(SeedEntryState::new()),
+                TitleOption::Options => GameFlow::Options(OptionsState::new()),
+                TitleOption::Help => GameFlow::Help(HelpState::new(GameFlow::Title(self.clone()))),
+                TitleOption::Credits => GameFlow::Credits,
+                TitleOption::Quit => GameFlow::Quitting,
+            });
         }
 
         None
     }
 
-    fn max_options(&self) -> usize {
-        if self.has_save_game { 5 } else { 4 }
-    }
-
     fn title_color_line(&self, current: usize) -> (Color, Option<Color>) {
         if current == self.selection {
             (BLUE, Some(WHITE))
@@ -1,17 +1,19 @@
 use macroquad::input::get_keys_pressed;
 use macroquad::window::clear_background;
 
+use crate::campaign::RunSetup;
 use crate::prelude::*;
 use crate::screens::title::TitleState;
 
 #[derive(Debug, Clone)]
 pub struct VictoryState {
     frame: usize,
+    setup: RunSetup,
 }
 
 impl VictoryState {
-    pub fn new() -> Self {
-        Self { frame: 0 }
+    pub fn new(setup: RunSetup) -> Self {
+        Self { frame: 0, setup }
     }
 
     pub fn process_frame(&mut self, screen: &Screen) -> Option<GameFlow> {
@@ -39,6 +41,9 @@ impl VictoryState {
         );
         offset += 40.0;
 
+        Screen::draw_centered_text(&self.setup.describe(), 22, offset, Some(GRAY));
+        offset += 40.0;
+
         Screen::draw_centered_text("Press any key", 22, offset, Some(GRAY));
 
         if self.frame > 10 && get_keys_pressed().iter().len() > 0 {
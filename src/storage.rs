@@ -0,0 +1,108 @@
+// Where options and saves are kept. Keys are relative paths like "saves/slot1.sav",
+// which are files under the data directory on desktop and localStorage entries on the web
+
+pub use backend::*;
+
+#[cfg(feature = "desktop")]
+mod backend {
+    use std::path::PathBuf;
+
+    pub fn path(key: &str) -> PathBuf {
+        let dirs = directories::ProjectDirs::from("com", "", "Archivist")
+            .expect("Unable to find project directory?");
+        dirs.data_dir().join(key)
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        std::fs::read_to_string(path(key)).ok()
+    }
+
+    pub fn write(key: &str, contents: &str) {
+        let filename = path(key);
+
+        match std::fs::create_dir_all(filename.parent().expect("Project dir should be longer")) {
+            Ok(()) => {
+                if let Err(e) = std::fs::write(filename, contents) {
+                    eprintln!("Unable to write {key}: {e:?}");
+                }
+            }
+            Err(e) => {
+                eprintln!("Unable to create location for {key}: {e:?}");
+            }
+        }
+    }
+
+    pub fn exists(key: &str) -> bool {
+        std::fs::exists(path(key)).unwrap_or(false)
+    }
+
+    pub fn remove(key: &str) {
+        if exists(key) && std::fs::remove_file(path(key)).is_err() {
+            eprintln!("Unable to delete {key}.");
+        }
+    }
+}
+
+// Backed by the archivist_storage plugin in web/js.js
+#[cfg(all(not(feature = "desktop"), target_arch = "wasm32"))]
+mod backend {
+    unsafe extern "C" {
+        fn archivist_storage_set(
+            key: *const u8,
+            key_len: usize,
+            value: *const u8,
+            value_len: usize,
+        );
+        fn archivist_storage_length(key: *const u8, key_len: usize) -> i32;
+        fn archivist_storage_get(
+            key: *const u8,
+            key_len: usize,
+            buffer: *mut u8,
+            buffer_len: usize,
+        );
+        fn archivist_storage_remove(key: *const u8, key_len: usize);
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        let length = unsafe { archivist_storage_length(key.as_ptr(), key.len()) };
+        if length < 0 {
+            return None;
+        }
+
+        let mut buffer = vec![0; length as usize];
+        unsafe {
+            archivist_storage_get(key.as_ptr(), key.len(), buffer.as_mut_ptr(), buffer.len())
+        };
+        String::from_utf8(buffer).ok()
+    }
+
+    pub fn write(key: &str, contents: &str) {
+        unsafe {
+            archivist_storage_set(key.as_ptr(), key.len(), contents.as_ptr(), contents.len())
+        };
+    }
+
+    pub fn exists(key: &str) -> bool {
+        unsafe { archivist_storage_length(key.as_ptr(), key.len()) >= 0 }
+    }
+
+    pub fn remove(key: &str) {
+        unsafe { archivist_storage_remove(key.as_ptr(), key.len()) };
+    }
+}
+
+// Native builds without the desktop feature have nowhere to keep anything
+#[cfg(all(not(feature = "desktop"), not(target_arch = "wasm32")))]
+mod backend {
+    pub fn read(_key: &str) -> Option<String> {
+        None
+    }
+
+    pub fn write(_key: &str, _contents: &str) {}
+
+    pub fn exists(_key: &str) -> bool {
+        false
+    }
+
+    pub fn remove(_key: &str) {}
+}